@@ -0,0 +1,114 @@
+//! Known-correct answers, stored in a small TOML file so refactors can be checked against them.
+//!
+//! The file has a table per day with a key per part:
+//!
+//!   [day1]
+//!   part1 = 514579
+//!   part2 = 241861950
+//!
+//! Values can be integers or quoted strings. Only this subset of TOML is understood.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+/// Default location of the answers file.
+pub const DEFAULT_PATH: &str = "answers.toml";
+
+/// Expected answers keyed by (day, part).
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers(pub BTreeMap<(usize, usize), String>);
+
+impl Answers {
+
+    /// Loads answers from the given file. A missing file is an error with ErrorKind::NotFound.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Answers, Error> {
+        Answers::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parses the contents of an answers file.
+    pub fn parse(contents: &str) -> Result<Answers, Error> {
+        let mut answers = BTreeMap::new();
+        let mut day = None;
+
+        for (i, line) in contents.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = |msg: &str| Error::new(ErrorKind::InvalidData, format!("answers line {}: {}: '{}'", i + 1, msg, line));
+
+            // Table header, [dayN]
+            if line.starts_with('[') {
+                let name = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')).ok_or_else(|| invalid("unclosed table header"))?;
+                day = Some(number_after(name.trim(), "day").ok_or_else(|| invalid("expected a [dayN] table"))?);
+                continue;
+            }
+
+            // partN = value
+            let (key, value) = line.split_once('=').ok_or_else(|| invalid("expected 'partN = answer'"))?;
+            let part = number_after(key.trim(), "part").ok_or_else(|| invalid("expected a part1 or part2 key"))?;
+            let day = day.ok_or_else(|| invalid("answer outside of a [dayN] table"))?;
+            let value = value.trim();
+            let value = if let Some(quoted) = value.strip_prefix('"') {
+                quoted.strip_suffix('"').ok_or_else(|| invalid("unterminated string"))?.to_string()
+            } else if value.parse::<i64>().is_ok() {
+                value.to_string()
+            } else {
+                return Err(invalid("answers must be integers or quoted strings"))
+            };
+            answers.insert((day, part), value);
+        }
+        Ok(Answers(answers))
+    }
+
+    /// The expected answer for the given day/part, if there is one.
+    pub fn get(&self, day: usize, part: usize) -> Option<&str> {
+        self.0.get(&(day, part)).map(|s| s.as_str())
+    }
+
+    pub fn insert(&mut self, day: usize, part: usize, answer: String) {
+        self.0.insert((day, part), answer);
+    }
+
+    /// Writes the answers back out in the same format they're read in.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let mut out = String::new();
+        let mut current_day = None;
+        for ((day, part), answer) in &self.0 {
+            if current_day != Some(*day) {
+                if current_day.is_some() {
+                    out.push('\n');
+                }
+                let _ = writeln!(out, "[day{}]", day);
+                current_day = Some(*day);
+            }
+            if answer.parse::<i64>().is_ok() {
+                let _ = writeln!(out, "part{} = {}", part, answer);
+            } else {
+                let _ = writeln!(out, "part{} = \"{}\"", part, answer);
+            }
+        }
+        fs::write(path, out)
+    }
+}
+
+// Removes a trailing # comment, ignoring any # inside a quoted string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {},
+        }
+    }
+    line
+}
+
+// Parses the number following a prefix, e.g. 7 from "day7".
+fn number_after(s: &str, prefix: &str) -> Option<usize> {
+    s.strip_prefix(prefix)?.parse().ok()
+}
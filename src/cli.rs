@@ -0,0 +1,379 @@
+//! Command line handling for the runner.
+//!
+//! Usage:
+//!   advent_of_code run <days> [--part N] [--input PATH] [--bench N] [--format text|json|csv]
+//!   advent_of_code verify [<days>] [--input PATH] [--answers FILE] [--save]
+//!   advent_of_code audit [--input PATH] [--rule NAME]... [--format text|json]
+//!   advent_of_code trajectory [--slope RIGHT,DOWN] [--input PATH] [--color]
+//!   advent_of_code passports [--input PATH] [--schema FILE] [--format text|json] [--export jsonl|csv] [--lenient]
+//!   advent_of_code list
+//!
+//! Where <days> is either a single day (7), an inclusive range (3..=6), an exclusive range (3..7),
+//! a comma separated list of any of those (1,3..=5) or `all`.
+//!
+//! --input can be a directory holding dayN_input.txt files, a single input file, or `-` for stdin.
+//! Without it inputs are read from $AOC_INPUT_DIR, or the current directory if that isn't set.
+//!
+//! --bench runs each day N times and reports the min/median/max time of each step instead of the answers.
+//!
+//! --format picks how answers are printed: a text table (the default), JSON or CSV.
+//!
+//! verify runs the given days (all of them by default) and compares the answers to the ones stored in the answers file,
+//! exiting with a non-zero status on any mismatch or answer with nothing stored for it. --save stores the current answers in the file instead.
+//!
+//! audit checks every day 2 password against the given policy rules (all of them by default) and reports per line
+//! whether it's valid, what each rule found and why it failed, followed by a count of each failure reason.
+//!
+//! trajectory draws the day 3 map with the path of a slope (3,1 by default) marked on it.
+//!
+//! passports checks each day 4 passport against a schema file (the puzzle's rules by default) and lists every field
+//! error of each invalid passport, along with where the passport is in the batch file. --export prints the valid
+//! passports' typed fields as JSON lines or CSV instead. --lenient tidies up heights written like "6ft", "183CM" or
+//! 5'11" before checking them. Entries are split on whitespace, so a height can't have spaces in it.
+
+use std::fmt;
+
+pub const USAGE: &str = "Usage:
+    advent_of_code run <days> [--part N] [--input PATH] [--bench N] [--format text|json|csv]
+                    Run the given days, e.g. `run 7`, `run 3..=6`, `run all`.
+                    PATH is a directory of dayN_input.txt files, a single input file or `-` for stdin,
+                    it defaults to $AOC_INPUT_DIR or the current directory.
+                    --bench repeats each day N times and reports min/median/max timings.
+                    --format prints the answers as a text table (default), JSON or CSV.
+    advent_of_code verify [<days>] [--input PATH] [--answers FILE] [--save]
+                    Check the answers of the given days (default all) against FILE (default answers.toml),
+                    exits non-zero on any mismatch or missing answer. --save records the current answers in FILE instead.
+    advent_of_code audit [--input PATH] [--rule NAME]... [--format text|json]
+                    Report which day 2 passwords pass each policy rule (default all of them) and why they fail.
+    advent_of_code trajectory [--slope RIGHT,DOWN] [--input PATH] [--color]
+                    Draw the day 3 map with the path of a slope (default 3,1) on it, X for trees hit and O for
+                    open squares landed on. --color highlights them with ANSI colors.
+    advent_of_code passports [--input PATH] [--schema FILE] [--format text|json] [--export jsonl|csv] [--lenient]
+                    List the field errors of every invalid day 4 passport under the rules in FILE
+                    (default the puzzle's rules) with the passport's position and lines in the batch.
+                    --export prints the valid passports as JSON lines or CSV instead.
+                    --lenient accepts heights like `6ft`, `183CM` or `1.8m`, converting them to cm or in.
+    advent_of_code list
+                    List the available days and parts
+    advent_of_code help
+                    Show this message";
+
+/// What the runner has been asked to do.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(RunOptions),
+    Verify(VerifyOptions),
+    Audit(AuditOptions),
+    Trajectory(TrajectoryOptions),
+    Passports(PassportOptions),
+    List,
+    Help,
+}
+
+/// Options for the run command.
+#[derive(Debug, PartialEq)]
+pub struct RunOptions {
+    pub days: DaySelection,
+    pub part: Option<usize>,
+    pub input: Option<String>,
+    /// Number of times to repeat each day when benchmarking.
+    pub bench: Option<usize>,
+    pub format: Format,
+}
+
+/// Options for the verify command.
+#[derive(Debug, PartialEq)]
+pub struct VerifyOptions {
+    pub days: DaySelection,
+    pub input: Option<String>,
+    /// Path to the answers file, defaults to answers.toml.
+    pub answers: Option<String>,
+    /// Store the current answers instead of checking them.
+    pub save: bool,
+}
+
+/// Options for the audit command.
+#[derive(Debug, PartialEq)]
+pub struct AuditOptions {
+    pub input: Option<String>,
+    /// Names of the rules to check, all registered rules if empty.
+    pub rules: Vec<String>,
+    pub format: Format,
+}
+
+/// Options for the trajectory command.
+#[derive(Debug, PartialEq)]
+pub struct TrajectoryOptions {
+    pub input: Option<String>,
+    /// The (right, down) slope to draw.
+    pub slope: (usize, usize),
+    pub color: bool,
+}
+
+/// Options for the passports command.
+#[derive(Debug, PartialEq)]
+pub struct PassportOptions {
+    pub input: Option<String>,
+    /// Path to a schema file, the puzzle's rules are used without one.
+    pub schema: Option<String>,
+    pub format: Format,
+    /// Print the valid passports in this format instead of the error report.
+    pub export: Option<Export>,
+    /// Normalize heights with lenient parsing before checking them.
+    pub lenient: bool,
+}
+
+/// Formats valid passports can be exported in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Export {
+    /// A JSON object per line.
+    JsonLines,
+    Csv,
+}
+
+/// How answers are printed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+/// Which days to run. `All` is resolved against the available days by the runner.
+#[derive(Debug, PartialEq)]
+pub enum DaySelection {
+    All,
+    Days(Vec<usize>),
+}
+
+impl DaySelection {
+    /// Returns true if the given day is part of this selection.
+    pub fn contains(&self, day: usize) -> bool {
+        match self {
+            DaySelection::All => true,
+            DaySelection::Days(days) => days.contains(&day),
+        }
+    }
+}
+
+/// Error returned when the command line can't be understood.
+#[derive(Debug, PartialEq)]
+pub struct UsageError(pub String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\n\n{}", self.0, USAGE)
+    }
+}
+
+/// Parses the program arguments (without the program name) into a Command.
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, UsageError> {
+    let mut args = args.into_iter();
+
+    match args.next().as_deref() {
+        None | Some("help") | Some("--help") | Some("-h") => Ok(Command::Help),
+        Some("list") => {
+            if let Some(extra) = args.next() {
+                return Err(UsageError(format!("unexpected argument '{}'", extra)))
+            }
+            Ok(Command::List)
+        },
+        Some("run") => {
+            let mut days = None;
+            let mut part = None;
+            let mut input = None;
+            let mut bench = None;
+            let mut format = Format::Text;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--part" | "-p" => {
+                        let value = args.next().ok_or_else(|| UsageError("--part needs a value".to_string()))?;
+                        part = Some(parse_part(&value)?);
+                    },
+                    "--input" | "-i" => {
+                        input = Some(args.next().ok_or_else(|| UsageError("--input needs a path".to_string()))?);
+                    },
+                    "--bench" | "-b" => {
+                        let value = args.next().ok_or_else(|| UsageError("--bench needs a number of runs".to_string()))?;
+                        match value.parse::<usize>() {
+                            Ok(n) if n > 0 => bench = Some(n),
+                            _ => return Err(UsageError(format!("invalid number of runs '{}'", value))),
+                        }
+                    },
+                    "--format" | "-f" => {
+                        let value = args.next().ok_or_else(|| UsageError("--format needs a value".to_string()))?;
+                        format = parse_format(&value)?;
+                    },
+                    _ if days.is_none() => days = Some(parse_days(&arg)?),
+                    _ => return Err(UsageError(format!("unexpected argument '{}'", arg))),
+                }
+            }
+            let days = days.ok_or_else(|| UsageError("run needs a day, range of days or 'all'".to_string()))?;
+            if bench.is_some() && format != Format::Text {
+                return Err(UsageError("--bench only supports the text format".to_string()))
+            }
+            Ok(Command::Run(RunOptions { days, part, input, bench, format }))
+        },
+        Some("verify") => {
+            let mut days = None;
+            let mut input = None;
+            let mut answers = None;
+            let mut save = false;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--input" | "-i" => {
+                        input = Some(args.next().ok_or_else(|| UsageError("--input needs a path".to_string()))?);
+                    },
+                    "--answers" | "-a" => {
+                        answers = Some(args.next().ok_or_else(|| UsageError("--answers needs a path".to_string()))?);
+                    },
+                    "--save" => save = true,
+                    _ if days.is_none() => days = Some(parse_days(&arg)?),
+                    _ => return Err(UsageError(format!("unexpected argument '{}'", arg))),
+                }
+            }
+            Ok(Command::Verify(VerifyOptions { days: days.unwrap_or(DaySelection::All), input, answers, save }))
+        },
+        Some("audit") => {
+            let mut input = None;
+            let mut rules = Vec::new();
+            let mut format = Format::Text;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--input" | "-i" => {
+                        input = Some(args.next().ok_or_else(|| UsageError("--input needs a path".to_string()))?);
+                    },
+                    "--rule" | "-r" => {
+                        rules.push(args.next().ok_or_else(|| UsageError("--rule needs a rule name".to_string()))?);
+                    },
+                    "--format" | "-f" => {
+                        let value = args.next().ok_or_else(|| UsageError("--format needs a value".to_string()))?;
+                        format = parse_format(&value)?;
+                    },
+                    _ => return Err(UsageError(format!("unexpected argument '{}'", arg))),
+                }
+            }
+            if format == Format::Csv {
+                return Err(UsageError("audit only supports the text and json formats".to_string()))
+            }
+            Ok(Command::Audit(AuditOptions { input, rules, format }))
+        },
+        Some("trajectory") => {
+            let mut input = None;
+            let mut slope = (3, 1);
+            let mut color = false;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--input" | "-i" => {
+                        input = Some(args.next().ok_or_else(|| UsageError("--input needs a path".to_string()))?);
+                    },
+                    "--slope" | "-s" => {
+                        let value = args.next().ok_or_else(|| UsageError("--slope needs a value".to_string()))?;
+                        slope = parse_slope(&value)?;
+                    },
+                    "--color" => color = true,
+                    _ => return Err(UsageError(format!("unexpected argument '{}'", arg))),
+                }
+            }
+            Ok(Command::Trajectory(TrajectoryOptions { input, slope, color }))
+        },
+        Some("passports") => {
+            let mut input = None;
+            let mut schema = None;
+            let mut format = Format::Text;
+            let mut export = None;
+            let mut lenient = false;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--input" | "-i" => {
+                        input = Some(args.next().ok_or_else(|| UsageError("--input needs a path".to_string()))?);
+                    },
+                    "--schema" | "-s" => {
+                        schema = Some(args.next().ok_or_else(|| UsageError("--schema needs a path".to_string()))?);
+                    },
+                    "--lenient" => lenient = true,
+                    "--export" | "-e" => {
+                        let value = args.next().ok_or_else(|| UsageError("--export needs a format".to_string()))?;
+                        export = match value.as_str() {
+                            "jsonl" => Some(Export::JsonLines),
+                            "csv" => Some(Export::Csv),
+                            _ => return Err(UsageError(format!("invalid export format '{}', expected jsonl or csv", value))),
+                        };
+                    },
+                    "--format" | "-f" => {
+                        let value = args.next().ok_or_else(|| UsageError("--format needs a value".to_string()))?;
+                        format = parse_format(&value)?;
+                    },
+                    _ => return Err(UsageError(format!("unexpected argument '{}'", arg))),
+                }
+            }
+            if format == Format::Csv {
+                return Err(UsageError("passports only supports the text and json formats".to_string()))
+            }
+            if export.is_some() && format != Format::Text {
+                return Err(UsageError("--export replaces the report, it can't be used with --format".to_string()))
+            }
+            Ok(Command::Passports(PassportOptions { input, schema, format, export, lenient }))
+        },
+        Some(other) => Err(UsageError(format!("unknown command '{}'", other))),
+    }
+}
+
+// Parses an output format name.
+fn parse_format(s: &str) -> Result<Format, UsageError> {
+    match s {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        "csv" => Ok(Format::Csv),
+        _ => Err(UsageError(format!("invalid format '{}', expected text, json or csv", s))),
+    }
+}
+
+// Parses a slope in the form 'right,down'. It has to go down, otherwise it never reaches the bottom.
+fn parse_slope(s: &str) -> Result<(usize, usize), UsageError> {
+    let slope = s.split_once(',').and_then(|(right, down)| Some((right.trim().parse().ok()?, down.trim().parse().ok()?)));
+    match slope {
+        Some((right, down)) if down > 0 => Ok((right, down)),
+        _ => Err(UsageError(format!("invalid slope '{}', expected RIGHT,DOWN with DOWN at least 1", s))),
+    }
+}
+
+// Parses a part number, only parts 1 and 2 exist.
+fn parse_part(s: &str) -> Result<usize, UsageError> {
+    match s.parse::<usize>() {
+        Ok(p) if p == 1 || p == 2 => Ok(p),
+        _ => Err(UsageError(format!("invalid part '{}', expected 1 or 2", s))),
+    }
+}
+
+// Parses a day selection in the form of 'all', 'n', 'n..=m', 'n..m' or a comma separated list of those.
+fn parse_days(s: &str) -> Result<DaySelection, UsageError> {
+    if s == "all" {
+        return Ok(DaySelection::All)
+    }
+
+    let mut days = Vec::new();
+    for spec in s.split(',').filter(|spec| !spec.is_empty()) {
+        if let Some((start, end)) = spec.split_once("..=") {
+            days.extend(parse_day(start)?..=parse_day(end)?);
+        } else if let Some((start, end)) = spec.split_once("..") {
+            days.extend(parse_day(start)?..parse_day(end)?);
+        } else {
+            days.push(parse_day(spec)?);
+        }
+    }
+    if days.is_empty() {
+        return Err(UsageError(format!("'{}' doesn't select any days", s)))
+    }
+    days.sort_unstable();
+    days.dedup();
+    Ok(DaySelection::Days(days))
+}
+
+// Parses a single day number (1-25).
+fn parse_day(s: &str) -> Result<usize, UsageError> {
+    match s.trim().parse::<usize>() {
+        Ok(d) if (1..=25).contains(&d) => Ok(d),
+        _ => Err(UsageError(format!("invalid day '{}', expected a number from 1 to 25", s))),
+    }
+}
@@ -1,35 +1,361 @@
-/// Advent of Code Day 1
-/// 
-/// Input is many rows of a number per line.
+//! Advent of Code Day 1
+//! 
+//! Input is many rows of a number per line.
 
-use std::fs;
-use std::io::Error;
+use std::collections::HashMap;
+use std::fmt;
 
+use crate::error::Error;
+use crate::solution::{Answer, Solution};
 
-/// Day 1 Part 1 is just finding the first two entries which sum to 2020 and return the multiple of them.
-pub fn part_one() -> Result<Option<usize>, std::io::Error> {
-    let list: Vec<usize> = fs::read_to_string("./day1_input.txt")?.split("\n").filter_map(|x| if let Ok(v) = x.parse::<usize>() {Some(v)} else {None}).collect();
-    for i in &list {
-        for x in &list {
-            if i + x == 2020 {
-                return Ok(Some(i*x))
+/// The sum both parts are looking for.
+pub const TARGET: usize = 2020;
+
+
+/// Day 1 solution.
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Parsed = Vec<usize>;
+
+    fn day(&self) -> usize {
+        1
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<usize>, Error> {
+        input.lines().enumerate().map(|(i, l)| {
+            l.trim().parse::<usize>().map_err(|_| Error::parse_at(i + 1, l, l.trim(), "expected a number"))
+        }).collect()
+    }
+
+    /// Day 1 Part 1 is just finding the first two entries which sum to 2020 and return the multiple of them.
+    fn part_one(&self, list: &Vec<usize>) -> Result<Answer, Error> {
+        find_k_sum(list, 2, TARGET).map(|sum| Answer::from(sum.product())).ok_or(Error::NoSolution)
+    }
+
+    /// Day 1 Part 2 is the same as part 1 but instead finding the first 3 entries which sum to 2020, and returning the multiple of them.
+    fn part_two(&self, list: &Vec<usize>) -> Result<Answer, Error> {
+        find_k_sum(list, 3, TARGET).map(|sum| Answer::from(sum.product())).ok_or(Error::NoSolution)
+    }
+}
+
+/// A set of entries that add up to the target, found by find_k_sum.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KSum {
+    /// Indices of the entries in the original list, in ascending order.
+    pub indices: Vec<usize>,
+    /// The entries themselves, in the same order as indices.
+    pub values: Vec<usize>,
+}
+
+impl KSum {
+
+    // Builds a KSum from (value, index) pairs, ordering them by index.
+    fn from_entries(mut entries: Vec<(usize, usize)>) -> KSum {
+        entries.sort_unstable_by_key(|(_, i)| *i);
+        KSum {
+            indices: entries.iter().map(|(_, i)| *i).collect(),
+            values: entries.iter().map(|(v, _)| *v).collect(),
+        }
+    }
+
+    /// The entries multiplied together, which is what the puzzle wants.
+    pub fn product(&self) -> usize {
+        self.values.iter().product()
+    }
+
+    /// Combines the entries in the given way.
+    pub fn aggregate(&self, aggregation: Aggregation) -> Aggregate {
+        match aggregation {
+            Aggregation::Product => Aggregate::Number(self.product()),
+            Aggregation::Sum => Aggregate::Number(self.values.iter().sum()),
+            Aggregation::Values => Aggregate::Values(self.values.clone()),
+        }
+    }
+}
+
+/// Finds k entries of the list, each at a different index, which add up to target.
+/// Pairs are found with a hash map of the entries seen so far, for k >= 3 the list is sorted and all but the last two
+/// entries are fixed in turn, with the last two found by walking in from both ends of what's left.
+/// Returns None if there are no such entries (or k is 0).
+pub fn find_k_sum(list: &[usize], k: usize, target: usize) -> Option<KSum> {
+    match k {
+        2 => {
+            let mut seen: HashMap<usize, usize> = HashMap::new();
+            for (i, v) in list.iter().enumerate() {
+                if let Some(j) = target.checked_sub(*v).and_then(|rest| seen.get(&rest)) {
+                    return Some(KSum::from_entries(vec![(list[*j], *j), (*v, i)]))
+                }
+                seen.entry(*v).or_insert(i);
+            }
+            None
+        },
+        _ => k_sums(list, k, target).next(),
+    }
+}
+
+/// Every distinct set of k entries which add up to target. Sets made up of the same values are only returned once,
+/// even if the values appear several times in the list.
+pub fn k_sums(list: &[usize], k: usize, target: usize) -> KSums {
+    let mut sorted: Vec<(usize, usize)> = list.iter().cloned().zip(0..).collect();
+    sorted.sort_unstable();
+    KSums { sorted, k, target, fixed: Vec::new(), pointers: None, started: false }
+}
+
+/// The number of distinct sets of k entries which add up to target.
+pub fn count_k_sums(list: &[usize], k: usize, target: usize) -> usize {
+    k_sums(list, k, target).count()
+}
+
+/// Iterator over the k sums of a list, see k_sums.
+///
+/// The list is sorted by value, then the first k - 2 entries are stepped through like an odometer (skipping repeated
+/// values and stopping once they're too big on their own) and for each of those the last two are found by walking in
+/// from both ends of the rest of the list. Sums come out in ascending order of their values.
+#[derive(Debug)]
+pub struct KSums {
+    sorted: Vec<(usize, usize)>,
+    k: usize,
+    target: usize,
+    // Positions in sorted of the entries currently fixed, all but the last two.
+    fixed: Vec<usize>,
+    // The (low, high) positions of the last two entries and the sum they need to make, while they're being searched for.
+    pointers: Option<(usize, usize, usize)>,
+    started: bool,
+}
+
+impl KSums {
+
+    // Moves the fixed entries on to the next set of values worth searching. Returns false once there are none left.
+    fn advance(&mut self) -> bool {
+        if !self.started {
+            self.started = true;
+            return self.fill(0, 0)
+        }
+        for level in (0..self.fixed.len()).rev() {
+            let current = self.sorted[self.fixed[level]].0;
+            let mut next = self.fixed[level] + 1;
+            while next < self.sorted.len() && self.sorted[next].0 == current {
+                next += 1;
+            }
+            if self.fill(level, next) {
+                return true
+            }
+        }
+        false
+    }
+
+    // Fixes the entries from level on to consecutive positions starting at start, which is the smallest sum they can make
+    // from there. Returns false if they don't fit in the list or already add up to more than the target.
+    fn fill(&mut self, level: usize, start: usize) -> bool {
+        let count = self.k - 2;
+        if start + (count - level) + 2 > self.sorted.len() {
+            return false
+        }
+        self.fixed.truncate(level);
+        self.fixed.extend(start..start + count - level);
+
+        let fixed_sum: usize = self.fixed.iter().map(|i| self.sorted[*i].0).sum();
+        match self.target.checked_sub(fixed_sum) {
+            Some(rest) => {
+                let low = self.fixed.last().map_or(0, |i| i + 1);
+                self.pointers = Some((low, self.sorted.len() - 1, rest));
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+impl Iterator for KSums {
+    type Item = KSum;
+
+    fn next(&mut self) -> Option<KSum> {
+        // Single entries don't need any searching, there's either one matching value or none.
+        if self.k < 2 {
+            if self.k == 0 || self.started {
+                return None
+            }
+            self.started = true;
+            return self.sorted.iter().find(|(v, _)| *v == self.target).map(|entry| KSum::from_entries(vec![*entry]))
+        }
+
+        loop {
+            while let Some((mut low, mut high, rest)) = self.pointers {
+                if low >= high {
+                    self.pointers = None;
+                    break
+                }
+                let (low_value, high_value) = (self.sorted[low].0, self.sorted[high].0);
+                let sum = low_value + high_value;
+                if sum == rest {
+                    let mut entries: Vec<(usize, usize)> = self.fixed.iter().map(|i| self.sorted[*i]).collect();
+                    entries.push(self.sorted[low]);
+                    entries.push(self.sorted[high]);
+
+                    // Step both ends past their values so the same pair isn't found again.
+                    while low < high && self.sorted[low].0 == low_value {
+                        low += 1;
+                    }
+                    while high > low && self.sorted[high].0 == high_value {
+                        high -= 1;
+                    }
+                    self.pointers = Some((low, high, rest));
+                    return Some(KSum::from_entries(entries))
+                } else if sum < rest {
+                    low += 1;
+                } else {
+                    high -= 1;
+                }
+                self.pointers = Some((low, high, rest));
+            }
+
+            if !self.advance() {
+                return None
             }
         }
     }
-    Ok(None)
 }
 
-/// Day 1 Part 2 is the same as part 1 but instead finding the first 3 entries which sum to 2020, and returning the multiple of them.
-pub fn part_two() -> Result<Option<usize>, std::io::Error> {
-    let list: Vec<usize> = fs::read_to_string("./day1_input.txt")?.split("\n").filter_map(|x| if let Ok(v) = x.parse::<usize>() {Some(v)} else {None}).collect();
-    for i in &list {
-        for x in &list {
-            for z in &list {
-                if i + x + z == 2020 {
-                    return Ok(Some(i*x*z))
+/// How the entries of a k sum are combined into a single result.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Aggregation {
+    Product,
+    Sum,
+    /// The entries themselves, uncombined.
+    Values,
+}
+
+/// The result of combining a k sum's entries.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Aggregate {
+    Number(usize),
+    Values(Vec<usize>),
+}
+
+impl fmt::Display for Aggregate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Aggregate::Number(n) => write!(f, "{}", n),
+            Aggregate::Values(values) => {
+                let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                write!(f, "({})", values.join(", "))
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_k_sums, find_k_sum, k_sums};
+
+    const EXAMPLE: [usize; 6] = [1721, 979, 366, 299, 675, 1456];
+
+    #[test]
+    fn finds_pairs_with_the_hash_map() {
+        let pair = find_k_sum(&EXAMPLE, 2, 2020).unwrap();
+        assert_eq!((pair.indices, pair.values), (vec![0, 3], vec![1721, 299]));
+    }
+
+    #[test]
+    fn finds_triples_by_sorting() {
+        let triple = find_k_sum(&EXAMPLE, 3, 2020).unwrap();
+        assert_eq!((&triple.indices, &triple.values), (&vec![1, 2, 4], &vec![979, 366, 675]));
+        assert_eq!(triple.product(), 241861950);
+    }
+
+    #[test]
+    fn never_reuses_an_entry() {
+        assert_eq!(find_k_sum(&[1010], 2, 2020), None);
+        assert_eq!(find_k_sum(&[1010, 7, 1010], 2, 2020).unwrap().indices, vec![0, 2]);
+        assert_eq!(find_k_sum(&[5, 5], 3, 15), None);
+        assert_eq!(find_k_sum(&[5, 1, 5, 5], 3, 15).unwrap().indices, vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn handles_small_and_large_k() {
+        assert_eq!(find_k_sum(&EXAMPLE, 0, 2020), None);
+        assert_eq!(find_k_sum(&EXAMPLE, 1, 366).unwrap().indices, vec![2]);
+        assert_eq!(find_k_sum(&EXAMPLE, 1, 2020), None);
+        assert_eq!(find_k_sum(&EXAMPLE, 7, 2020), None);
+        assert_eq!(find_k_sum(&EXAMPLE, 6, EXAMPLE.iter().sum()).unwrap().indices, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    // Every distinct set of values from k different entries adding up to target, by trying every combination.
+    fn brute_force(list: &[usize], k: usize, target: usize) -> Vec<Vec<usize>> {
+        fn combinations(list: &[usize], k: usize, chosen: &mut Vec<usize>, found: &mut Vec<Vec<usize>>) {
+            if chosen.len() == k {
+                found.push(chosen.clone());
+                return
+            }
+            for (i, v) in list.iter().enumerate() {
+                chosen.push(*v);
+                combinations(&list[i + 1..], k, chosen, found);
+                chosen.pop();
+            }
+        }
+        let mut found = Vec::new();
+        combinations(list, k, &mut Vec::new(), &mut found);
+        let mut sums: Vec<Vec<usize>> = found.into_iter()
+            .filter(|c| c.iter().sum::<usize>() == target)
+            .map(|mut c| { c.sort_unstable(); c })
+            .collect();
+        sums.sort();
+        sums.dedup();
+        sums
+    }
+
+    // The sorted values of each k sum, sorted.
+    fn sums(list: &[usize], k: usize, target: usize) -> Vec<Vec<usize>> {
+        let mut sums: Vec<Vec<usize>> = k_sums(list, k, target).map(|s| {
+            let mut values = s.values;
+            values.sort_unstable();
+            values
+        }).collect();
+        sums.sort();
+        sums
+    }
+
+    #[test]
+    fn k_sums_skip_repeated_combinations() {
+        let list = [1, 1, 1, 2, 2, 3];
+        assert_eq!(sums(&list, 3, 5), vec![vec![1, 1, 3], vec![1, 2, 2]]);
+        assert_eq!(sums(&list, 2, 4), vec![vec![1, 3], vec![2, 2]]);
+        assert_eq!(sums(&[3, 3, 4], 1, 3), vec![vec![3]]);
+    }
+
+    #[test]
+    fn k_sums_only_pair_a_value_with_itself_if_it_appears_twice() {
+        assert_eq!(count_k_sums(&[1010], 2, 2020), 0);
+        assert_eq!(count_k_sums(&[1010, 1010], 2, 2020), 1);
+        assert_eq!(count_k_sums(&[3, 5, 7], 3, 9), 0);
+        assert_eq!(count_k_sums(&[3, 3, 3], 3, 9), 1);
+    }
+
+    #[test]
+    fn k_sums_match_brute_force() {
+        let ranges: Vec<usize> = (1..=7).collect();
+        let repeats = [4, 1, 3, 1, 4, 2, 2, 5, 3, 1];
+        for list in [&ranges[..], &repeats[..], &EXAMPLE[..]] {
+            // k = 0 is left out, it never gives any sums.
+            for k in 1..=list.len() + 1 {
+                let most: usize = list.iter().sum();
+                for target in (0..=most).step_by(if most > 100 { 101 } else { 1 }).chain(Some(2020)) {
+                    assert_eq!(sums(list, k, target), brute_force(list, k, target), "{:?} k={} target={}", list, k, target);
                 }
             }
         }
+        assert_eq!(sums(&ranges, 4, 16).len(), 5);
     }
-    Ok(None)
-}
\ No newline at end of file
+
+    #[test]
+    fn count_k_sums_edge_cases() {
+        let list: Vec<usize> = (1..=7).collect();
+        assert_eq!(count_k_sums(&list, 0, 0), 0);
+        assert_eq!(count_k_sums(&list, 1, 7), 1);
+        assert_eq!(count_k_sums(&list, 1, 8), 0);
+        assert_eq!(count_k_sums(&list, 8, 28), 0);
+        assert_eq!(count_k_sums(&list, 7, 28), 1);
+        assert_eq!(count_k_sums(&list, 4, 16), k_sums(&list, 4, 16).count());
+    }
+}
@@ -1,88 +1,322 @@
-/// Advent of Code Day 2
-/// 
-/// Input is rows of passwords and their policy in the following format: 'n-m X: password'
-
-use std::io::Error;
-use std::fs;
-
-/// Day 2 Part 1 returns the amount of usable passwords in the input file, using the policy: X is a letter, n is the amount of times and m is the max amount of times that letter can occur in the password.
-pub fn part_one() -> Result<usize, Error> {
-    let input = fs::read_to_string("./day2_input.txt")?;
-    let lines: Vec<&str> = input.split("\n").collect();
-
-    let valid_passwords = lines.iter().filter(|l| {
-        if let Some(info) = extract_line_information(l) {
-            let letter_count = info.0.chars().filter(|x| x == &info.1).count();
-            if letter_count >= info.2 && letter_count <= info.3 {
-                true
-            } else {false}
+//! Advent of Code Day 2
+//! 
+//! Input is rows of passwords and their policy in the following format: 'n-m X: password'
+//!
+//! What n and m mean depends on which rule the policy is checked with, part 1 and part 2 each use a different one.
+//! Extra rules can be added by implementing PolicyRule and registering them in a RuleRegistry.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::solution::{Answer, Solution};
+
+/// Day 2 solution.
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Parsed = Vec<PasswordEntry>;
+
+    fn day(&self) -> usize {
+        2
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<PasswordEntry>, Error> {
+        input.lines().enumerate().map(|(i, l)| l.parse::<PasswordEntry>().map_err(|e| e.at_line(i + 1))).collect()
+    }
+
+    /// Day 2 Part 1 returns the amount of usable passwords in the input file, using the policy: X is a letter, n is the amount of times and m is the max amount of times that letter can occur in the password.
+    fn part_one(&self, entries: &Vec<PasswordEntry>) -> Result<Answer, Error> {
+        Ok(count_valid(entries, &CountRange).into())
+    }
+
+    /// Day 2 Part 2 changes the policy, where in 'n-m X: password' the letter x must occur at either position n or m in the password, and not both.
+    /// Returns the amount of usable passwords.
+    fn part_two(&self, entries: &Vec<PasswordEntry>) -> Result<Answer, Error> {
+        Ok(count_valid(entries, &ExactlyOnePosition).into())
+    }
+}
+
+/// The policy part of a line, 'n-m X'.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PasswordPolicy {
+    pub letter: char,
+    /// n, the first number of the policy.
+    pub first: usize,
+    /// m, the second number of the policy.
+    pub second: usize,
+}
+
+impl FromStr for PasswordPolicy {
+    type Err = Error;
+
+    /// Parses a policy (n-m X). Errors are reported on line 1, use Error::at_line to move them to the line's real position.
+    fn from_str(s: &str) -> Result<PasswordPolicy, Error> {
+        parse_policy(s, s)
+    }
+}
+
+/// A single line of the input, a password and the policy it has to follow.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasswordEntry {
+    pub policy: PasswordPolicy,
+    pub password: String,
+}
+
+impl FromStr for PasswordEntry {
+    type Err = Error;
+
+    /// Parses a password/policy line (n-m X: password), or returns a parse error pointing at the part of the line that's wrong.
+    /// Errors are reported on line 1, use Error::at_line to move them to the line's real position.
+    fn from_str(s: &str) -> Result<PasswordEntry, Error> {
+        let (policy, password) = s.split_once(':').ok_or_else(|| Error::parse_at(1, s, s, "expected 'n-m X: password'"))?;
+        let policy = parse_policy(s, policy.trim_end())?;
+        let password = password.strip_prefix(' ').unwrap_or(password);
+        Ok(PasswordEntry { policy, password: password.to_string() })
+    }
+}
+
+// Parses the policy part of a line (n-m X). `policy` must be a slice of `line` so errors point at the right column.
+fn parse_policy(line: &str, policy: &str) -> Result<PasswordPolicy, Error> {
+    let invalid = |part: &str, message: &str| Error::parse_at(1, line, part, message);
+
+    let (range, letter) = policy.split_once(' ').ok_or_else(|| invalid(policy, "expected a range and a letter before ':'"))?;
+
+    // Parse the n-m range.
+    let (first, second) = range.split_once('-').ok_or_else(|| invalid(range, "expected a range in the form n-m"))?;
+    let first = first.parse::<usize>().map_err(|_| invalid(first, "expected a number"))?;
+    let second = second.parse::<usize>().map_err(|_| invalid(second, "expected a number"))?;
+
+    // The policy letter must be a single character.
+    let mut chars = letter.chars();
+    let letter = match (chars.next(), chars.next()) {
+        (Some(c), None) => c,
+        _ => return Err(invalid(letter, "expected a single letter")),
+    };
+    Ok(PasswordPolicy { letter, first, second })
+}
+
+/// A way of interpreting a policy to decide whether a password is valid.
+pub trait PolicyRule {
+    /// Name the rule is registered under.
+    fn name(&self) -> &str;
+
+    /// Checks the password against the policy under this rule, saying what was looked at and why it failed if it did.
+    fn check(&self, policy: &PasswordPolicy, password: &str) -> RuleCheck;
+
+    /// Returns true if the password follows the policy under this rule.
+    fn is_valid(&self, policy: &PasswordPolicy, password: &str) -> bool {
+        self.check(policy, password).valid
+    }
+}
+
+/// The outcome of checking one password against one rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleCheck {
+    pub valid: bool,
+    /// What the rule looked at to decide.
+    pub observed: Observed,
+    /// Why the password failed, None if it's valid. Kept short as the audit summary groups failures by it.
+    pub reason: Option<String>,
+}
+
+impl RuleCheck {
+
+    /// A passing check.
+    pub fn pass(observed: Observed) -> RuleCheck {
+        RuleCheck { valid: true, observed, reason: None }
+    }
+
+    /// A failing check with the reason it failed.
+    pub fn fail<R: Into<String>>(observed: Observed, reason: R) -> RuleCheck {
+        RuleCheck { valid: false, observed, reason: Some(reason.into()) }
+    }
+}
+
+/// What a rule looked at in the password.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Observed {
+    /// How many times the policy letter occurs.
+    Count(usize),
+    /// The character at each (1 based) position checked, None if the position is 0 or past the end of the password.
+    Positions(Vec<(usize, Option<char>)>),
+}
+
+impl fmt::Display for Observed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Observed::Count(n) => write!(f, "count {}", n),
+            Observed::Positions(positions) => {
+                let positions: Vec<String> = positions.iter().map(|(pos, c)| match c {
+                    Some(c) => format!("{} is '{}'", pos, c),
+                    None => format!("{} is out of range", pos),
+                }).collect();
+                write!(f, "position {}", positions.join(", "))
+            },
+        }
+    }
+}
+
+/// Part 1's rule, the letter has to occur between n and m times (inclusive). A policy with n greater than m always fails.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CountRange;
+
+impl PolicyRule for CountRange {
+    fn name(&self) -> &str {
+        "count-range"
+    }
+
+    fn check(&self, policy: &PasswordPolicy, password: &str) -> RuleCheck {
+        let letter_count = password.chars().filter(|c| *c == policy.letter).count();
+        let observed = Observed::Count(letter_count);
+        if policy.first > policy.second {
+            RuleCheck::fail(observed, "min greater than max")
+        } else if letter_count < policy.first {
+            RuleCheck::fail(observed, "too few")
+        } else if letter_count > policy.second {
+            RuleCheck::fail(observed, "too many")
         } else {
-            false
+            RuleCheck::pass(observed)
         }
-    }).count();
-    Ok(valid_passwords)
-}
-
-
-/// Day 2 Part 2 changes the policy, where in 'n-m X: password' the letter x must occur at either position n or m in the password, and not both.
-/// Returns the amount of usable passwords.
-pub fn part_two() -> Result<usize, Error> {
-    let input = fs::read_to_string("./day2_input.txt")?;
-    let lines: Vec<&str> = input.split("\n").collect();
-
-    let valid_passwords = lines.iter().filter(|l| {
-        if let Some(info) = extract_line_information(l) {
-            let indexable: Vec<char> = info.0.split("").filter_map(|x| {
-                if x.len() <= 0 {
-                    None
-                } else {
-                    Some(x.parse::<char>().unwrap())
-                }
-            }).collect();
-            let pos1 = indexable[info.2-1];
-            let pos2 = indexable[info.3-1];
-            match (pos1 == info.1, pos2 == info.1) {
-                (true, true) => false,
-                (false, false) => false,
-                _ => true,
-            }
-        } else {false}
-    }).count();
-    Ok(valid_passwords)
-}
-
-// Takes a password/policy line (n-m X: password) and extracts the useful information from it.
-// If line information is able to be extracted returns a tuple of (password, letter, min, max).
-fn extract_line_information(s: &str) -> Option<(String, char, usize, usize)> {
-    // Check if line is valid
-    if s.len() <= 0 && !s.contains(":") {
-        return None
-    }
-
-    let (mut min, mut max, mut letter) = (0, 0, ' ');
-    let mut stage = 0;
-    let mut current = String::new();
-    for x in s.chars() {
-        match x {
-            '-' => {
-                min = current.parse::<usize>().unwrap();
-                current.clear();
-            },
-            ' ' => {
-                if stage == 0 {
-                    max = current.parse::<usize>().unwrap();
-                    current.clear();
-                    stage = 1;
-                }
-            },
-            ':' => {
-                letter = current.chars().next().unwrap();
-                current.clear();
-            },
-            _ => {
-                current.push(x);
+    }
+}
+
+/// Part 2's rule, the letter has to be at exactly one of the (1 based) positions n and m.
+/// Positions are counted in characters, not bytes, and a position outside of the password never matches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExactlyOnePosition;
+
+impl PolicyRule for ExactlyOnePosition {
+    fn name(&self) -> &str {
+        "exactly-one-position"
+    }
+
+    fn check(&self, policy: &PasswordPolicy, password: &str) -> RuleCheck {
+        let pos1 = char_at(password, policy.first);
+        let pos2 = char_at(password, policy.second);
+        let observed = Observed::Positions(vec![(policy.first, pos1), (policy.second, pos2)]);
+
+        // Exactly one of the two positions must match, one that's out of range never does.
+        match (pos1 == Some(policy.letter), pos2 == Some(policy.letter)) {
+            (true, true) => RuleCheck::fail(observed, "both positions match"),
+            (false, false) => RuleCheck::fail(observed, "neither position matches"),
+            _ => RuleCheck::pass(observed),
+        }
+    }
+}
+
+// The character at a 1 based position of the password, counting chars rather than bytes.
+// None for position 0 or one past the end of the password.
+fn char_at(password: &str, position: usize) -> Option<char> {
+    password.chars().nth(position.checked_sub(1)?)
+}
+
+/// The rules passwords can be checked against, looked up by name.
+pub struct RuleRegistry {
+    rules: Vec<Box<dyn PolicyRule>>,
+}
+
+impl RuleRegistry {
+
+    /// A registry without any rules in it.
+    pub fn empty() -> RuleRegistry {
+        RuleRegistry { rules: Vec::new() }
+    }
+
+    /// Adds a rule, replacing any existing rule with the same name.
+    pub fn register(&mut self, rule: Box<dyn PolicyRule>) {
+        self.rules.retain(|r| r.name() != rule.name());
+        self.rules.push(rule);
+    }
+
+    /// Looks up a rule by name.
+    pub fn get(&self, name: &str) -> Option<&dyn PolicyRule> {
+        self.rules.iter().find(|r| r.name() == name).map(|r| r.as_ref())
+    }
+
+    /// Every registered rule, in the order they were registered.
+    pub fn rules(&self) -> impl Iterator<Item = &dyn PolicyRule> {
+        self.rules.iter().map(|r| r.as_ref())
+    }
+}
+
+impl Default for RuleRegistry {
+    /// A registry holding the rules from both parts of the puzzle.
+    fn default() -> RuleRegistry {
+        let mut registry = RuleRegistry::empty();
+        registry.register(Box::new(CountRange));
+        registry.register(Box::new(ExactlyOnePosition));
+        registry
+    }
+}
+
+/// Counts the passwords that are valid under the given rule.
+pub fn count_valid(entries: &[PasswordEntry], rule: &dyn PolicyRule) -> usize {
+    entries.iter().filter(|e| rule.is_valid(&e.policy, &e.password)).count()
+}
+
+/// The result of checking every line of an input against a set of rules.
+#[derive(Debug)]
+pub struct Audit {
+    pub lines: Vec<AuditLine>,
+}
+
+/// How a single line of the input fared in an audit.
+#[derive(Debug)]
+pub struct AuditLine {
+    /// 1 based line number in the input.
+    pub line: usize,
+    pub text: String,
+    /// Each rule's name and check, or why the line couldn't be parsed.
+    pub checks: Result<Vec<(String, RuleCheck)>, Error>,
+}
+
+impl AuditLine {
+
+    /// Returns true if the line parsed and passed every rule.
+    pub fn is_valid(&self) -> bool {
+        match &self.checks {
+            Ok(checks) => checks.iter().all(|(_, check)| check.valid),
+            Err(_) => false,
+        }
+    }
+}
+
+impl Audit {
+
+    /// The number of lines that passed every rule.
+    pub fn valid_count(&self) -> usize {
+        self.lines.iter().filter(|l| l.is_valid()).count()
+    }
+
+    /// How many times each failure happened, keyed by "rule: reason". Lines that couldn't be parsed count as "malformed".
+    pub fn failure_histogram(&self) -> BTreeMap<String, usize> {
+        let mut histogram = BTreeMap::new();
+        for line in &self.lines {
+            match &line.checks {
+                Ok(checks) => {
+                    for (rule, check) in checks {
+                        if let Some(reason) = &check.reason {
+                            *histogram.entry(format!("{}: {}", rule, reason)).or_insert(0) += 1;
+                        }
+                    }
+                },
+                Err(_) => *histogram.entry(String::from("malformed")).or_insert(0) += 1,
             }
         }
+        histogram
     }
-    Some((current, letter, min, max))
-}
\ No newline at end of file
+}
+
+/// Checks every line of the input against each of the rules. Unlike Day2::parse a malformed line doesn't stop the audit,
+/// it's reported along with the rest.
+pub fn audit(input: &str, rules: &[&dyn PolicyRule]) -> Audit {
+    let lines = input.lines().enumerate().map(|(i, l)| {
+        let checks = l.parse::<PasswordEntry>().map(|entry| {
+            rules.iter().map(|rule| (rule.name().to_string(), rule.check(&entry.policy, &entry.password))).collect()
+        }).map_err(|e| e.at_line(i + 1));
+        AuditLine { line: i + 1, text: l.to_string(), checks }
+    }).collect();
+    Audit { lines }
+}
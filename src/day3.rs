@@ -1,127 +1,186 @@
-/// Advent of Code Day 3
-/// 
-/// Taking an input of multiple lines which contain '.'s and '#'.
-/// '.' represents an open space, '#' represents a tree
+//! Advent of Code Day 3
+//! 
+//! Taking an input of multiple lines which contain '.'s and '#'.
+//! '.' represents an open space, '#' represents a tree
 
-use std::io::Error;
-use std::fs;
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
 
-/// Part 1 returns the number of trees you would hit if you continued down the lines in a 3 right down 1 pattern.
-pub fn part_one() -> Result<usize, Error> {
-    let input = fs::read_to_string("./day3_input.txt")?;
-    let lines: Vec<&str> = input.split("\n").collect();
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::solution::{Answer, Solution};
 
+/// Day 3 solution.
+pub struct Day3;
 
-    let mut map = TobogganTracker::new(lines);
+impl Solution for Day3 {
+    type Parsed = Grid<Cell>;
+
+    fn day(&self) -> usize {
+        3
+    }
+
+    /// The map repeats to the right, so the grid wraps its columns.
+    fn parse(&self, input: &str) -> Result<Grid<Cell>, Error> {
+        Ok(Grid::parse(input, "expected '.' or '#'", Cell::from_char)?.wrapping(false, true))
+    }
+
+    /// Part 1 returns the number of trees you would hit if you continued down the lines in a 3 right down 1 pattern.
+    fn part_one(&self, map: &Grid<Cell>) -> Result<Answer, Error> {
+        Ok(trees_on_slope(map, 3, 1).into())
+    }
+
+    /// Part 2 returns the number of trees you would hit if you multipled the amount of trees you'd hit on the following slopes (right x, down x): r1d1, r3d1, r5d1, r7d1, r1d2 
+    fn part_two(&self, map: &Grid<Cell>) -> Result<Answer, Error> {
+        Ok(trees_on_slopes(map, &PART_TWO_SLOPES).iter().product::<usize>().into())
+    }
+}
+
+/// The (right, down) slopes checked in part 2.
+pub const PART_TWO_SLOPES: [(usize, usize); 5] = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+
+/// Counts the trees hit going from the top left to the bottom of the map, moving right and down by the given amounts each step.
+/// A slope that doesn't go down never reaches the bottom, so it hits no trees.
+pub fn trees_on_slope(map: &Grid<Cell>, right: usize, down: usize) -> usize {
+    if down == 0 {
+        return 0
+    }
+    let mut tracker = TobogganTracker::new(map);
     let mut hit_trees = 0;
-    while map.row < map._max_row {
-        map.move_pos(1, 3);
-        if map.get_pos() == Some("#") {
+    while !tracker.at_bottom() {
+        tracker.move_pos(down, right);
+        if tracker.get_pos() == Some(Cell::Tree) {
             hit_trees += 1;
         }
     }
-    Ok(hit_trees)
+    hit_trees
 }
 
-/// Part 2 returns the number of trees you would hit if you multipled the amount of trees you'd hit on the following slopes (right x, down x): r1d1, r3d1, r5d1, r7d1, r1d2 
-pub fn part_two() -> Result<usize, Error> {
-    let input = fs::read_to_string("./day3_input.txt")?;
-    let lines: Vec<&str> = input.split("\n").collect();
-
-    let mut map = TobogganTracker::new(lines);
-
-    let mut results: (usize, usize, usize, usize, usize) = (0, 0, 0, 0, 0);
+/// Counts the trees hit on each of the (right, down) slopes, in the same order as the slopes.
+pub fn trees_on_slopes(map: &Grid<Cell>, slopes: &[(usize, usize)]) -> Vec<usize> {
+    slopes.iter().map(|(right, down)| trees_on_slope(map, *right, *down)).collect()
+}
 
-    // Slope of r1,d1
-    while map.row < map._max_row {
-        map.move_pos(1, 1);
-        if map.get_pos() == Some("#") {
-            results.0 += 1;
+/// Tries every slope within the given right and down ranges and returns the one that hits the fewest trees, along with
+/// how many it hits. Ties go to the slope with the smallest right, then smallest down, step.
+/// Only down steps from 1 up to one less than the map's height are tried: 0 never gets anywhere, and anything bigger
+/// jumps straight off the bottom without landing on a single square, which would make it "safest" every time.
+/// Returns None if there aren't any slopes to try.
+pub fn safest_slope(map: &Grid<Cell>, rights: RangeInclusive<usize>, downs: RangeInclusive<usize>) -> Option<((usize, usize), usize)> {
+    let mut safest: Option<((usize, usize), usize)> = None;
+    for right in rights {
+        for down in downs.clone().filter(|d| *d > 0 && *d < map.height()) {
+            let trees = trees_on_slope(map, right, down);
+            if safest.is_none_or(|(_, fewest)| trees < fewest) {
+                safest = Some(((right, down), trees));
+            }
         }
     }
-    map.row = 0;
-    map.column = 0;
+    safest
+}
 
-    // Slope of r3, d1
-    while map.row < map._max_row {
-        map.move_pos(1, 3);
-        if map.get_pos() == Some("#") {
-            results.1 += 1;
-        }
+/// Draws the map with the path of a slope on it, X where a tree is hit and O where the toboggan lands on an open square.
+/// The map is repeated to the right as many times as the path needs. With `color` the marks are highlighted with ANSI
+/// escape codes, trees hit in red and open squares in green.
+pub fn render_trajectory(map: &Grid<Cell>, right: usize, down: usize, color: bool) -> String {
+    if map.width() == 0 {
+        return String::new()
     }
-    map.row = 0;
-    map.column = 0;
 
-    // Slope of r5, d1
-    while map.row < map._max_row {
-        map.move_pos(1, 5);
-        if map.get_pos() == Some("#") {
-            results.2 += 1;
+    // Follow the slope with the tracker itself, so the drawing shows exactly where the solvers land.
+    let mut path = HashSet::new();
+    let mut tracker = TobogganTracker::new(map);
+    while down > 0 && !tracker.at_bottom() {
+        tracker.move_pos(down, right);
+        if tracker.get_pos().is_some() {
+            path.insert((tracker.row, tracker.column));
         }
     }
-    map.row = 0;
-    map.column = 0;
 
-    // Slope of r7, d1
-    while map.row < map._max_row {
-        map.move_pos(1, 7);
-        if map.get_pos() == Some("#") {
-            results.3 += 1;
+    let furthest = path.iter().map(|(_, column)| column + 1).max().unwrap_or(0);
+    let drawn_width = furthest.max(map.width()).div_ceil(map.width()) * map.width();
+
+    let mut out = String::new();
+    for row in 0..map.height() {
+        for column in 0..drawn_width {
+            let cell = map.get(row, column).copied().unwrap_or(Cell::Open);
+            let mark = match (cell, path.contains(&(row, column)), color) {
+                (Cell::Tree, true, true) => "\x1b[1;31mX\x1b[0m",
+                (Cell::Tree, true, false) => "X",
+                (Cell::Open, true, true) => "\x1b[1;32mO\x1b[0m",
+                (Cell::Open, true, false) => "O",
+                (Cell::Tree, false, _) => "#",
+                (Cell::Open, false, _) => ".",
+            };
+            out.push_str(mark);
         }
+        out.push('\n');
     }
-    map.row = 0;
-    map.column = 0;
+    out
+}
+
+/// A single square of the map.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cell {
+    Open,
+    Tree,
+}
 
-    // Slope of r1, d2
-    while map.row < map._max_row {
-        map.move_pos(2, 1);
-        if map.get_pos() == Some("#") {
-            results.4 += 1;
+impl Cell {
+
+    /// The cell for a map character, '.' or '#'.
+    pub fn from_char(c: char) -> Option<Cell> {
+        match c {
+            '.' => Some(Cell::Open),
+            '#' => Some(Cell::Tree),
+            _ => None,
         }
     }
-    map.row = 0;
-    map.column = 0;
-
-    Ok(results.0 * results.1 * results.2 * results.3 * results.4)
 }
 
-/// Helper struct to make tracking the toboggan map easier.
-struct TobogganTracker<'a> {
-    map: Vec<&'a str>,
-    row: usize,
-    column: usize,
-    _max_column: usize,
-    _max_row: usize,
+/// Helper struct to make tracking the toboggan map easier. It's a position on the map, the map itself wraps the column.
+pub struct TobogganTracker<'a> {
+    map: &'a Grid<Cell>,
+    pub row: usize,
+    /// How far right the toboggan has gone in total, this isn't wrapped to the width of the map.
+    pub column: usize,
 }
 
 impl TobogganTracker<'_> {
     
-    /// Create a new instance from a Vec of toboggan lines (&str).
-    fn new(l: Vec<&str>) -> TobogganTracker {
-        TobogganTracker {
-            row: 0,
-            column: 0,
-            _max_column: l[0].len(),
-            _max_row: l.len()-1,
-            map: l,
-        }
+    /// Create a new instance at the top left of the map.
+    pub fn new(map: &Grid<Cell>) -> TobogganTracker<'_> {
+        TobogganTracker { map, row: 0, column: 0 }
     }
 
-    // Takes a row/column offset to move by.
-    fn move_pos(&mut self, row: usize, col: usize) {
-        let mut new_column = self.column + col;
-        while new_column >= self._max_column {
-            new_column -= self._max_column;
-        }
-        self.column = new_column;
+    /// Takes a row/column offset to move by.
+    pub fn move_pos(&mut self, row: usize, col: usize) {
         self.row += row;
+        self.column += col;
     }
 
-    // Get value at current row/column offset.
-    fn get_pos(&self) -> Option<&str> {
-        if let Some(row) = self.map.get(self.row) {
-            return row.get(self.column..self.column+1);
-        }
-        None
+    /// Get the cell at the current position, None once it's past the bottom of the map.
+    pub fn get_pos(&self) -> Option<Cell> {
+        self.map.get(self.row, self.column).copied()
+    }
+
+    /// Returns true once the toboggan has reached (or gone past) the last row of the map.
+    pub fn at_bottom(&self) -> bool {
+        self.row + 1 >= self.map.height()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{safest_slope, trees_on_slope, Day3};
+    use crate::solution::Solution;
+
+    #[test]
+    fn safest_slope_stays_on_the_map() {
+        let map = Day3.parse("...\n###\n###\n").unwrap();
+        assert_eq!(trees_on_slope(&map, 0, 3), 0);
+        // Every slope that lands anywhere hits a tree, but the ones jumping off the bottom aren't counted as safe.
+        assert_eq!(safest_slope(&map, 0..=2, 1..=5), Some(((0, 2), 1)));
+        assert_eq!(safest_slope(&map, 0..=2, 3..=5), None);
+    }
+}
@@ -1,135 +1,542 @@
-/// Advent of Code Day 4
-/// 
-/// Input is in a batch file of key:value pairs separated by spaces or newlines. Separate passports are separated by blank lines.
-/// There are eight possible fields on each passport: byr, iyr, eyr, ght, hcl, ecl, pid, cid
-
-use std::io::Error;
-use std::fs;
+//! Advent of Code Day 4
+//! 
+//! Input is in a batch file of key:value pairs separated by spaces or newlines. Separate passports are separated by blank lines.
+//! There are eight possible fields on each passport: byr, iyr, eyr, ght, hcl, ecl, pid, cid
+//!
+//! What makes a passport valid is described by a Schema, PASSPORT_SCHEMA holds the puzzle's rules. Other rules can be
+//! loaded with Schema::load and checked the same way.
 
 use std::collections::HashMap;
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::measure::{MeasureError, Measurement, Mode, Unit};
+use crate::schema::{FieldError, FieldErrorKind, Schema};
+use crate::solution::{Answer, Solution};
+
+/// Day 4 solution.
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Parsed = Vec<PassportRecord>;
+
+    fn day(&self) -> usize {
+        4
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Error> {
+        PassportReader::new(input.as_bytes()).map(|r| r.map_err(Error::from)).collect()
+    }
+
+    /// Day 4 Part 1 returns the amount of valid passports (have all fields but cid).
+    fn part_one(&self, passports: &Self::Parsed) -> Result<Answer, Error> {
+        let schema = passport_schema();
+        let valid_passports = passports.iter().filter(|p| schema.has_required(&p.fields)).count();
+        Ok(valid_passports.into())
+    }
+
+
+    /// Day 4 Part 2 returns the amount of valid passports, which are passports which have the required fields and pass a validation test on them.
+    fn part_two(&self, passports: &Self::Parsed) -> Result<Answer, Error> {
+        let schema = passport_schema();
+        let valid_passports = passports.iter().filter(|p| Passport::from_fields(&p.fields, &schema).is_ok()).count();
+        Ok(valid_passports.into())
+    }
+}
 
-/// Day 4 Part 1 returns the amount of valid passports (have all fields but cid).
-pub fn part_one() -> Result<usize, Error> {
-    let input = fs::read_to_string("./day4_input.txt")?;
-    
-    let passports: Vec<HashMap<&str, &str>> = input.split("\n\n").map(|l| {
-        l.split_whitespace().filter_map(|entry| {
-            let mut split = entry.split(":");
-            if let (Some(k), Some(v)) = (split.next(), split.next()) {
-                Some((k, v))
-            } else {None}
-        }).collect()
-    }).collect();
-
-    let valid_passports = passports.iter().filter(|p| {
-        p.keys().filter(|k| **k != "cid").count() >= 7
-    }).count();
-    
-    Ok(valid_passports)
-}
-
-
-/// Day 4 Part 2 returns the amount of valid passports, which are passports which have the required fields and pass a validation test on them.
-pub fn part_two() -> Result<usize, Error> {
-    let input = fs::read_to_string("./day4_input.txt")?;
-    
-    let passports: Vec<HashMap<&str, &str>> = input.split("\n\n").map(|l| {
-        l.split_whitespace().filter_map(|entry| {
-            let mut split = entry.split(":");
-            if let (Some(k), Some(v)) = (split.next(), split.next()) {
-                Some((k, v))
-            } else {None}
-        }).collect()
-    }).collect();
-
-    let valid_passports = passports.iter().filter(|p| {
-        validate_passport_fields(*p)
-    }).count();
-
-    Ok(valid_passports)
-}
-
-/// Checks if given passport has valid field values.
-fn validate_passport_fields(p: &HashMap<&str, &str>) -> bool {
-    // Check if passport has right number of min fields.
-    if p.keys().filter(|k|**k != "cid").count() < 7 {
-        return false
-    }
-    // Validate birth year field.
-    if let Some(byr) = p.get("byr") {
-        if let Ok(byr) = byr.parse::<usize>() {
-            if byr < 1920 || byr > 2002 {
-                return false
+/// A single passport from the batch file, along with where it is in the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PassportRecord {
+    /// 1 based position of the passport in the batch.
+    pub index: usize,
+    /// 1 based line numbers of the passport's first and last lines.
+    pub first_line: usize,
+    pub last_line: usize,
+    pub fields: HashMap<String, String>,
+}
+
+impl PassportRecord {
+
+    /// Rewrites the height into the form strict parsing expects (e.g. "6ft" into 72in), if lenient parsing can make
+    /// sense of it. Anything else is left as it is.
+    pub fn normalize_height(&mut self) {
+        if let Some(hgt) = self.fields.get_mut("hgt") {
+            if let Ok(height) = Height::parse(hgt, Mode::Lenient) {
+                *hgt = height.to_string();
             }
         }
-    } else {return false}
+    }
 
-    // Validate issue year field.
-    if let Some(iyr) = p.get("iyr") {
-        if let Ok(iyr) = iyr.parse::<usize>() {
-            if iyr < 2010 || iyr > 2020 {
-                return false
-            }
-        } else {return false}
-    } else {return false}
-
-    // Validate Expiration year field.
-    if let Some(eyr) = p.get("eyr") {
-        if let Ok(eyr) = eyr.parse::<usize>() {
-            if eyr < 2020 || eyr > 2030 {
-                return false
+    /// Everything wrong with the passport under the given schema, empty if it's valid.
+    pub fn check(&self, schema: &Schema) -> Vec<FieldError> {
+        schema.check(&self.fields)
+    }
+}
+
+/// Reads passports one at a time from a batch file, so the whole file never has to be in memory.
+///
+/// Passports are separated by lines that are empty or only whitespace, and lines can end with either \n or \r\n.
+/// A malformed entry gives a ReadError::Unreadable for its passport and reading carries on from the next one, an io error
+/// (including the file not being UTF-8) ends the iterator.
+pub struct PassportReader<R> {
+    reader: R,
+    line: String,
+    line_number: usize,
+    index: usize,
+    done: bool,
+}
+
+impl<R: BufRead> PassportReader<R> {
+
+    pub fn new(reader: R) -> PassportReader<R> {
+        PassportReader { reader, line: String::new(), line_number: 0, index: 0, done: false }
+    }
+
+    // Reads the next line into self.line without its line ending, returning false at the end of the input.
+    fn next_line(&mut self) -> Result<bool, Error> {
+        self.line.clear();
+        if self.reader.read_line(&mut self.line)? == 0 {
+            return Ok(false)
+        }
+        self.line_number += 1;
+        let trimmed = self.line.strip_suffix('\n').map(|l| l.strip_suffix('\r').unwrap_or(l)).unwrap_or(&self.line).len();
+        self.line.truncate(trimmed);
+        Ok(true)
+    }
+
+    // Reads lines up to the end of the current passport, or the input, returning the number of the passport's last line.
+    fn skip_passport(&mut self) -> Result<usize, Error> {
+        let mut last_line = self.line_number;
+        while self.next_line()? && !self.line.trim().is_empty() {
+            last_line = self.line_number;
+        }
+        Ok(last_line)
+    }
+
+    // Reads the next passport, None at the end of the input.
+    fn read_passport(&mut self) -> Result<Option<PassportRecord>, ReadError> {
+        let mut current: Option<PassportRecord> = None;
+        while self.next_line().map_err(ReadError::Io)? {
+            if self.line.trim().is_empty() {
+                if current.is_some() {
+                    break
+                }
+                continue;
             }
-        } else {return false}
-    } else {return false}
-
-    // Validate height field.
-    if let Some(hgt) = p.get("hgt") {
-        // Handle inch height
-        if hgt.contains("in") {
-            if let Ok(hgt) = hgt[..2].parse::<usize>() {
-                if hgt < 59 || hgt > 76 {
-                    return false
+            let record = current.get_or_insert_with(|| PassportRecord {
+                index: self.index + 1,
+                first_line: self.line_number,
+                last_line: self.line_number,
+                fields: HashMap::new(),
+            });
+            record.last_line = self.line_number;
+
+            // Each line holds space separated key:value entries.
+            for entry in self.line.split_whitespace() {
+                match entry.split_once(':') {
+                    Some((k, v)) => {
+                        record.fields.insert(k.to_string(), v.to_string());
+                    },
+                    None => {
+                        let error = Error::parse_at(self.line_number, &self.line, entry, "expected key:value");
+                        let (index, first_line) = (record.index, record.first_line);
+                        self.index += 1;
+                        let last_line = self.skip_passport().map_err(ReadError::Io)?;
+                        return Err(ReadError::Unreadable { index, first_line, last_line, error })
+                    },
                 }
-            } else {return false}
+            }
         }
-        // Handle cm height
-        else if hgt.contains("cm") {
-            if let Ok(hgt) = hgt[..3].parse::<usize>() {
-                if hgt < 150 || hgt > 193 {
-                    return false
-                }
-            } else {return false}
+        if current.is_some() {
+            self.index += 1;
         }
-        else {
-            return false
+        Ok(current)
+    }
+}
+
+impl<R: BufRead> Iterator for PassportReader<R> {
+    type Item = Result<PassportRecord, ReadError>;
+
+    fn next(&mut self) -> Option<Result<PassportRecord, ReadError>> {
+        if self.done {
+            return None
+        }
+        match self.read_passport() {
+            Ok(record) => {
+                self.done = record.is_none();
+                record.map(Ok)
+            },
+            Err(e @ ReadError::Io(_)) => {
+                self.done = true;
+                Some(Err(e))
+            },
+            Err(e) => Some(Err(e)),
         }
-    } else {return false}
+    }
+}
 
-    // Validate Hair color
-    if let Some(hcl) = p.get("hcl") {
-        // no regex, just manually set valid chars
-        let valid_chars = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f'];
-        if hcl.len() != 7 || &hcl[..1] != "#" || hcl[1..].chars().filter(|c| valid_chars.contains(c)).count() != 6 {
-            return false
+/// Why PassportReader couldn't give the next passport.
+#[derive(Debug)]
+pub enum ReadError {
+    /// The passport has an entry that isn't key:value. It still has its place in the batch, the reader carries on with
+    /// the next one.
+    Unreadable {
+        index: usize,
+        first_line: usize,
+        last_line: usize,
+        error: Error,
+    },
+    /// The input couldn't be read, nothing after this can be either.
+    Io(Error),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadError::Unreadable { index, first_line, last_line, error } => {
+                write!(f, "passport {} (lines {}-{}) is unreadable: {}", index, first_line, last_line, error)
+            },
+            ReadError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<ReadError> for Error {
+    /// The underlying error, a parse error pointing at the bad entry or the io error.
+    fn from(e: ReadError) -> Error {
+        match e {
+            ReadError::Unreadable { error, .. } | ReadError::Io(error) => error,
         }
     }
+}
+
+/// The puzzle's passport rules, in the format read by Schema::parse.
+pub const PASSPORT_SCHEMA: &str = "\
+# Birth, issue and expiration years.
+byr required int 1920..=2002
+iyr required int 2010..=2020
+eyr required int 2020..=2030
+# Height in centimetres or inches.
+hgt required unit cm:150..=193 in:59..=76
+hcl required pattern #[0-9a-f]{6}
+ecl required enum amb blu brn gry grn hzl oth
+pid required pattern [0-9]{9}
+# Country ID, North Pole credentials don't have one.
+cid optional any
+";
 
-    // Validate Eye color.
-    if let Some(ecl) = p.get("ecl") {
-        let valid_colors = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
-        if !valid_colors.contains(ecl) {
-            return false
+/// The schema for the puzzle's passport rules.
+pub fn passport_schema() -> Schema {
+    Schema::parse(PASSPORT_SCHEMA).expect("the built in passport schema is valid")
+}
+
+/// A valid passport with its fields parsed into their types.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Passport {
+    pub birth_year: u32,
+    pub issue_year: u32,
+    pub expiration_year: u32,
+    pub height: Height,
+    pub hair_color: Rgb,
+    pub eye_color: EyeColor,
+    /// Kept as a string, the leading zeros matter.
+    pub passport_id: String,
+    pub country_id: Option<String>,
+}
+
+impl Passport {
+
+    /// Checks the fields against the schema and, if they pass, parses them into a Passport. Fields the schema doesn't
+    /// know about don't stop it passing. Whatever the schema says, every field but cid is needed for a Passport.
+    /// Returns every field error if the fields don't pass or can't be turned into their types.
+    pub fn from_fields(fields: &HashMap<String, String>, schema: &Schema) -> Result<Passport, Vec<FieldError>> {
+        let mut errors = schema.check(fields);
+        if errors.iter().any(|e| e.kind.is_failure()) {
+            return Err(errors)
         }
-    } else {return false}
 
-    // Validate passport id.
-    if let Some(pid) = p.get("pid") {
-        if let Ok(_) = pid.parse::<usize>() {
-            if pid.len() != 9 {
-                return false
+        // A custom schema might let through values that don't fit the types, so they can still fail here.
+        let birth_year = typed_field(fields, "byr", &mut errors);
+        let issue_year = typed_field(fields, "iyr", &mut errors);
+        let expiration_year = typed_field(fields, "eyr", &mut errors);
+        let height = typed_field(fields, "hgt", &mut errors);
+        let hair_color = typed_field(fields, "hcl", &mut errors);
+        let eye_color = typed_field(fields, "ecl", &mut errors);
+        let passport_id = typed_field(fields, "pid", &mut errors);
+        match (birth_year, issue_year, expiration_year, height, hair_color, eye_color, passport_id) {
+            (Some(birth_year), Some(issue_year), Some(expiration_year), Some(height), Some(hair_color), Some(eye_color), Some(passport_id)) => {
+                Ok(Passport {
+                    birth_year, issue_year, expiration_year, height, hair_color, eye_color, passport_id,
+                    country_id: fields.get("cid").cloned(),
+                })
+            },
+            _ => Err(errors),
+        }
+    }
+}
+
+impl FromStr for Passport {
+    type Err = PassportError;
+
+    /// Parses a single passport's key:value entries (spread over any number of lines) and checks them against the
+    /// puzzle's rules.
+    fn from_str(s: &str) -> Result<Passport, PassportError> {
+        let mut fields = HashMap::new();
+        for (i, line) in s.lines().enumerate() {
+            for entry in line.split_whitespace() {
+                let (k, v) = entry.split_once(':').ok_or_else(|| Error::parse_at(i + 1, line, entry, "expected key:value"))?;
+                fields.insert(k.to_string(), v.to_string());
             }
-        } else {return false}
-    } else {return false}
-    true
+        }
+        Passport::from_fields(&fields, &passport_schema()).map_err(PassportError::Invalid)
+    }
+}
+
+/// Why a passport couldn't be parsed from text.
+#[derive(Debug)]
+pub enum PassportError {
+    /// The text isn't made of key:value entries.
+    Parse(Error),
+    /// The entries are there but some fields are wrong.
+    Invalid(Vec<FieldError>),
+}
+
+impl fmt::Display for PassportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PassportError::Parse(e) => write!(f, "{}", e),
+            PassportError::Invalid(errors) => {
+                let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                write!(f, "invalid passport: {}", errors.join(", "))
+            },
+        }
+    }
 }
 
+impl From<Error> for PassportError {
+    fn from(e: Error) -> PassportError {
+        PassportError::Parse(e)
+    }
+}
+
+// Parses a field into its type, adding an error for it if it's missing or doesn't parse.
+fn typed_field<T: FromStr>(fields: &HashMap<String, String>, name: &str, errors: &mut Vec<FieldError>) -> Option<T> {
+    let (value, kind) = match fields.get(name) {
+        Some(value) => match value.parse() {
+            Ok(typed) => return Some(typed),
+            Err(_) => (Some(value.clone()), FieldErrorKind::BadFormat),
+        },
+        None => (None, FieldErrorKind::Missing),
+    };
+    errors.push(FieldError { field: name.to_string(), value, kind });
+    None
+}
+
+/// A height and the unit it was given in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Height {
+    Cm(u32),
+    In(u32),
+}
+
+impl Height {
+
+    /// The number part of the height.
+    pub fn value(&self) -> u32 {
+        match self {
+            Height::Cm(n) | Height::In(n) => *n,
+        }
+    }
+
+    /// The unit part of the height, as it's written in a passport.
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Height::Cm(_) => "cm",
+            Height::In(_) => "in",
+        }
+    }
+
+    /// Parses a height. Strictly that's a whole number of cm or in, like 183cm or 60in. Leniently it can be written any
+    /// way Measurement::parse allows, and metric or imperial units are converted to cm or in and rounded.
+    pub fn parse(s: &str, mode: Mode) -> Result<Height, MeasureError> {
+        let measurement = Measurement::parse(s, mode)?;
+        let measurement = match (measurement.unit, mode) {
+            (Unit::Cm, _) | (Unit::In, _) => measurement,
+            (Unit::Mm, Mode::Lenient) | (Unit::M, Mode::Lenient) => measurement.convert(Unit::Cm),
+            (Unit::Ft, Mode::Lenient) => measurement.convert(Unit::In),
+            (unit, Mode::Strict) => return Err(MeasureError::UnitNotAllowed(unit)),
+        };
+
+        let value = match mode {
+            Mode::Strict => measurement.value,
+            Mode::Lenient => measurement.value.round(),
+        };
+        if value.fract() != 0.0 || value > u32::MAX as f64 {
+            return Err(MeasureError::BadNumber(measurement.value.to_string()))
+        }
+        match measurement.unit {
+            Unit::Cm => Ok(Height::Cm(value as u32)),
+            _ => Ok(Height::In(value as u32)),
+        }
+    }
+
+    /// The height as a measurement, for converting to other units.
+    pub fn measurement(&self) -> Measurement {
+        match self {
+            Height::Cm(n) => Measurement::new(*n as f64, Unit::Cm),
+            Height::In(n) => Measurement::new(*n as f64, Unit::In),
+        }
+    }
+}
+
+impl FromStr for Height {
+    type Err = FieldErrorKind;
+
+    /// Strictly parses a height like 183cm or 60in.
+    fn from_str(s: &str) -> Result<Height, FieldErrorKind> {
+        Height::parse(s, Mode::Strict).map_err(|_| FieldErrorKind::BadFormat)
+    }
+}
+
+impl fmt::Display for Height {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.value(), self.unit())
+    }
+}
+
+/// A colour as red, green and blue, written #rrggbb.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl FromStr for Rgb {
+    type Err = FieldErrorKind;
+
+    fn from_str(s: &str) -> Result<Rgb, FieldErrorKind> {
+        let hex = s.strip_prefix('#').filter(|h| h.len() == 6 && h.chars().all(|c| c.is_ascii_hexdigit()));
+        let hex = hex.ok_or(FieldErrorKind::BadFormat)?;
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| FieldErrorKind::BadFormat);
+        Ok(Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// The eye colours a passport can have.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EyeColor {
+    Amb,
+    Blu,
+    Brn,
+    Gry,
+    Grn,
+    Hzl,
+    Oth,
+}
+
+impl EyeColor {
+
+    /// The colour as it's written in a passport.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EyeColor::Amb => "amb",
+            EyeColor::Blu => "blu",
+            EyeColor::Brn => "brn",
+            EyeColor::Gry => "gry",
+            EyeColor::Grn => "grn",
+            EyeColor::Hzl => "hzl",
+            EyeColor::Oth => "oth",
+        }
+    }
+}
+
+impl FromStr for EyeColor {
+    type Err = FieldErrorKind;
+
+    fn from_str(s: &str) -> Result<EyeColor, FieldErrorKind> {
+        match s {
+            "amb" => Ok(EyeColor::Amb),
+            "blu" => Ok(EyeColor::Blu),
+            "brn" => Ok(EyeColor::Brn),
+            "gry" => Ok(EyeColor::Gry),
+            "grn" => Ok(EyeColor::Grn),
+            "hzl" => Ok(EyeColor::Hzl),
+            "oth" => Ok(EyeColor::Oth),
+            _ => Err(FieldErrorKind::BadFormat),
+        }
+    }
+}
+
+impl fmt::Display for EyeColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{passport_schema, Height, PassportReader, ReadError};
+    use crate::error::Error;
+    use crate::measure::{MeasureError, Mode, Unit};
+    use crate::schema::FieldErrorKind;
+
+    #[test]
+    fn reader_gives_positions_across_crlf_and_whitespace_separators() {
+        let input: &[u8] = b"ecl:gry pid:860033327\r\neyr:2020 hcl:#fffffd\r\n \t\r\nbyr:1937 iyr:2017\r\noops hgt:183cm\r\ncid:147\r\n \t\r\n\r\nhcl:#ae17e3 iyr:2013";
+        let mut reader = PassportReader::new(input);
+
+        let first = reader.next().unwrap().unwrap();
+        assert_eq!((first.index, first.first_line, first.last_line), (1, 1, 2));
+        assert_eq!(first.fields.len(), 4);
+        assert_eq!(first.fields["hcl"], "#fffffd");
+
+        match reader.next().unwrap() {
+            Err(ReadError::Unreadable { index: 2, first_line: 4, last_line: 6, error: Error::Parse { line: 5, .. } }) => (),
+            other => panic!("expected passport 2 to be unreadable, got {:?}", other),
+        }
+
+        let third = reader.next().unwrap().unwrap();
+        assert_eq!((third.index, third.first_line, third.last_line), (3, 9, 9));
+        assert_eq!(third.fields["iyr"], "2013");
+
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn heights() {
+        assert_eq!(Height::parse("183cm", Mode::Strict), Ok(Height::Cm(183)));
+        assert_eq!(Height::parse("cm150", Mode::Lenient), Ok(Height::Cm(150)));
+        assert!(Height::parse("cm150", Mode::Strict).is_err());
+        assert_eq!(Height::parse("183cmx", Mode::Lenient), Ok(Height::Cm(183)));
+        assert!(Height::parse("183cmx", Mode::Strict).is_err());
+        assert_eq!(Height::parse("5ft 11in", Mode::Lenient), Ok(Height::In(71)));
+        assert_eq!(Height::parse("6'2\"", Mode::Lenient), Ok(Height::In(74)));
+        assert!(Height::parse("1m80", Mode::Lenient).is_err());
+        assert_eq!(Height::parse("1.8m", Mode::Lenient), Ok(Height::Cm(180)));
+        assert_eq!(Height::parse("183.0cm", Mode::Lenient), Ok(Height::Cm(183)));
+        assert_eq!(Height::parse("183.0cm", Mode::Strict), Err(MeasureError::BadNumber(String::from("183.0"))));
+    }
+
+    #[test]
+    fn strict_heights_are_only_cm_or_in() {
+        assert_eq!(Height::parse("1830mm", Mode::Strict), Err(MeasureError::UnitNotAllowed(Unit::Mm)));
+        assert_eq!(Height::parse("2m", Mode::Strict), Err(MeasureError::UnitNotAllowed(Unit::M)));
+        assert_eq!(Height::parse("6ft", Mode::Strict), Err(MeasureError::UnitNotAllowed(Unit::Ft)));
+        assert_eq!(Height::parse("6ft", Mode::Lenient), Ok(Height::In(72)));
+    }
+
+    #[test]
+    fn heights_out_of_range_parse_but_fail_the_schema() {
+        assert_eq!(Height::parse("1900in", Mode::Strict), Ok(Height::In(1900)));
+        let fields: HashMap<String, String> = [("hgt", "1900in")].iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        let errors = passport_schema().check(&fields);
+        assert!(errors.iter().any(|e| e.field == "hgt" && e.kind == FieldErrorKind::OutOfRange));
+    }
+}
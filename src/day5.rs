@@ -1,105 +1,346 @@
-/// Advent of Code Day 5
-/// 
-/// Input is a list of strings which are 10 characters long. The first 7 are eiter F or B, and the last 3 are either R or L.
-/// Using rows numbered 0 through 127, each letter tells you which half of a region the given seat is in: 0-127, first letter is F bringing us down to 0-63
-/// 
-/// Both day solutions can be simplified a bit, but wanted to break it out into a few steps instead of one giant chained interator.
-
-use std::io::Error;
-use std::fs;
-
-/// Day 5 Part 1 is just getting the highest seat id of all input seats.
-pub fn part_one() -> Result<usize, Error> {
-    let input = fs::read_to_string("./day5_input.txt")?;
-    let seats: Vec<SeatRange> = input.split("\n").map(|l| l.chars().fold(SeatRange::new(), |s, c| { s.eval(c) })).collect();
-
-    let mut seat_ids: Vec<usize> = seats.iter().map(|s| s.id()).collect();
-    seat_ids.sort();
-    if let Some(v) = seat_ids.last() {
-        return Ok(*v)
-    }
-    Ok(0)
-}
+//! Advent of Code Day 5
+//! 
+//! Input is a list of strings which are 10 characters long. The first 7 are eiter F or B, and the last 3 are either R or L.
+//! Using rows numbered 0 through 127, each letter tells you which half of a region the given seat is in: 0-127, first letter is F bringing us down to 0-63
+//! 
+//! Other planes can be described with a Geometry, which sets the number of rows and columns, the letters and the seat id formula.
+//! 
+//! Both day solutions can be simplified a bit, but wanted to break it out into a few steps instead of one giant chained interator.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::solution::{Answer, Solution};
+
+/// Day 5 solution.
+pub struct Day5;
+
+impl Solution for Day5 {
+    /// Seat ids of every boarding pass, sorted.
+    type Parsed = Vec<usize>;
+
+    fn day(&self) -> usize {
+        5
+    }
 
-/// Day 5 Part 2 is finding the missing seat id (our seat id) and returning it.
-pub fn part_two() -> Result<usize, Error> {
-    let input = fs::read_to_string("./day5_input.txt")?;
-    let seats: Vec<SeatRange> = input.split("\n").map(|l| l.chars().fold(SeatRange::new(), |s, c| { s.eval(c) })).collect();
+    fn parse(&self, input: &str) -> Result<Vec<usize>, Error> {
+        let mut seat_ids = input.lines().enumerate().map(|(i, l)| {
+            BoardingPass::decode(l).map(|pass| pass.id()).map_err(|e| e.at_line(i + 1))
+        }).collect::<Result<Vec<usize>, Error>>()?;
+        seat_ids.sort();
+        Ok(seat_ids)
+    }
 
-    let mut seat_ids: Vec<usize> = seats.iter().map(|s| s.id()).collect();
-    seat_ids.sort();
+    /// Day 5 Part 1 is just getting the highest seat id of all input seats.
+    fn part_one(&self, seat_ids: &Vec<usize>) -> Result<Answer, Error> {
+        if let Some(v) = seat_ids.last() {
+            return Ok((*v).into())
+        }
+        Err(Error::NoSolution)
+    }
 
-    for (i, id) in seat_ids.iter().enumerate() {
-        if let Some(next_seat) = &seat_ids.get(i+1) {
-            if *next_seat - id > 1 {
-                return Ok(id+1)
+    /// Day 5 Part 2 is finding the missing seat id (our seat id) and returning it.
+    fn part_two(&self, seat_ids: &Vec<usize>) -> Result<Answer, Error> {
+        for (i, id) in seat_ids.iter().enumerate() {
+            if let Some(next_seat) = &seat_ids.get(i+1) {
+                if *next_seat - id > 1 {
+                    return Ok((id+1).into())
+                }
             }
         }
+        Err(Error::NoSolution)
     }
-    Ok(0)
 }
 
-/// Helper struct for dealing with seat range col/rows. Wanted to make it foldable because why not?
-#[derive(Debug)]
-struct SeatRange {
-    row: usize,
-    col: usize,
-    _row_start: usize,
-    _row_end: usize,
-    _col_start: usize,
-    _col_end: usize,
+/// The layout of a plane: how many rows and columns of seats it has, the letters boarding passes use for each half of
+/// the rows and columns, and how seat ids are worked out.
+#[derive(Debug, Clone, Copy)]
+pub struct Geometry {
+    rows: usize,
+    columns: usize,
+    row_letters: (char, char),
+    column_letters: (char, char),
+    id: IdFormula,
+}
+
+/// How a seat's id is worked out from its row and column.
+#[derive(Debug, Clone, Copy)]
+pub enum IdFormula {
+    /// row * columns + column, the puzzle's formula.
+    RowMajor,
+    /// column * rows + row.
+    ColumnMajor,
+    /// Any other formula, given the row and column.
+    Custom(fn(usize, usize) -> usize),
+}
+
+impl Default for Geometry {
+    /// The puzzle's plane, 128 rows (F/B) of 8 columns (L/R) with row major ids.
+    fn default() -> Geometry {
+        Geometry { rows: 128, columns: 8, row_letters: ('F', 'B'), column_letters: ('L', 'R'), id: IdFormula::RowMajor }
+    }
 }
 
-impl SeatRange {
+impl Geometry {
 
-    fn new() -> SeatRange {
-        SeatRange {
-            row: 0,
-            col: 0,
-            _row_start: 0,
-            _row_end: 127,
-            _col_start: 0,
-            _col_end: 7,
+    /// A plane with the given number of rows and columns, which must both be powers of two (1 included).
+    /// The letters are the (lower half, upper half) pair used for rows and columns, each pair must be two different letters.
+    /// Returns None if any of that doesn't hold.
+    pub fn new(rows: usize, columns: usize, row_letters: (char, char), column_letters: (char, char)) -> Option<Geometry> {
+        if !rows.is_power_of_two() || !columns.is_power_of_two() || row_letters.0 == row_letters.1 || column_letters.0 == column_letters.1 {
+            return None
         }
+        Some(Geometry { rows, columns, row_letters, column_letters, id: IdFormula::RowMajor })
     }
 
-    fn eval(self, c: char) -> SeatRange {
-        let mut seat_range = self;
-        match c {
-            'F' => {
-                if (seat_range._row_end - seat_range._row_start) == 1 {
-                    seat_range.row = seat_range._row_start;
-                } else {
-                    seat_range._row_end = seat_range._row_start + ((seat_range._row_end - seat_range._row_start)/2);    
-                }
-            },
-            'B' => {
-                if (seat_range._row_end - seat_range._row_start) == 1 {
-                    seat_range.row = seat_range._row_end;
-                } else {
-                    seat_range._row_start = seat_range._row_start + ((seat_range._row_end - seat_range._row_start)/2)+1;    
-                }
-            },
-            'L' => {
-                if (seat_range._col_end - seat_range._col_start) == 1 {
-                    seat_range.col = seat_range._col_start;
-                } else {
-                    seat_range._col_end = seat_range._col_start + ((seat_range._col_end - seat_range._col_start)/2);    
-                }
-            },
-            'R' => {
-                if (seat_range._col_end - seat_range._col_start) == 1 {
-                    seat_range.col = seat_range._col_end;
-                } else {
-                    seat_range._col_start = seat_range._col_start + ((seat_range._col_end - seat_range._col_start)/2)+1;    
-                }
+    /// Uses a different formula for seat ids.
+    pub fn with_id(mut self, id: IdFormula) -> Geometry {
+        self.id = id;
+        self
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Number of letters a boarding pass uses for the row.
+    pub fn row_bits(&self) -> usize {
+        self.rows.trailing_zeros() as usize
+    }
+
+    /// Number of letters a boarding pass uses for the column.
+    pub fn column_bits(&self) -> usize {
+        self.columns.trailing_zeros() as usize
+    }
+
+    /// The id of the seat at the given row and column.
+    pub fn id(&self, row: usize, col: usize) -> usize {
+        match self.id {
+            IdFormula::RowMajor => row * self.columns + col,
+            IdFormula::ColumnMajor => col * self.rows + row,
+            IdFormula::Custom(formula) => formula(row, col),
+        }
+    }
+
+    /// The (row, column) of the seat with the given id, None if no seat has it.
+    pub fn seat(&self, id: usize) -> Option<(usize, usize)> {
+        let (row, col) = match self.id {
+            IdFormula::RowMajor => (id / self.columns, id % self.columns),
+            IdFormula::ColumnMajor => (id % self.rows, id / self.rows),
+            // There's no telling how to undo a custom formula, so look through every seat for it.
+            IdFormula::Custom(formula) => {
+                return (0..self.rows).flat_map(|row| (0..self.columns).map(move |col| (row, col))).find(|(row, col)| formula(*row, *col) == id)
             },
-            _ => {}
+        };
+        if row < self.rows && col < self.columns { Some((row, col)) } else { None }
+    }
+}
+
+/// A seat on the plane, decoded from or encoded into a boarding pass.
+///
+/// A boarding pass is really a binary number: the first letter of each pair (F, L) is a 0 and the second (B, R) a 1.
+/// On the puzzle's plane the first 7 bits are the row and the last 3 the column, which makes the whole 10 bit number the
+/// seat id (row * 8 + column). The `_with` methods take a Geometry for other planes, the rest use the puzzle's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BoardingPass {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl BoardingPass {
+
+    /// Decodes a boarding pass like FBFBBFFRLR. Anything that isn't exactly 7 F/B letters followed by 3 L/R letters is
+    /// a parse error, reported on line 1 (use Error::at_line to move it).
+    pub fn decode(pass: &str) -> Result<BoardingPass, Error> {
+        BoardingPass::decode_with(pass, &Geometry::default())
+    }
+
+    /// Decodes a boarding pass for the given plane.
+    pub fn decode_with(pass: &str, geometry: &Geometry) -> Result<BoardingPass, Error> {
+        let (row_bits, column_bits) = (geometry.row_bits(), geometry.column_bits());
+        let (mut row, mut col) = (0, 0);
+        for (i, (offset, c)) in pass.char_indices().enumerate() {
+            let ((zero, one), value) = if i < row_bits { (geometry.row_letters, &mut row) } else { (geometry.column_letters, &mut col) };
+            let bit = match c {
+                _ if i >= row_bits + column_bits => break,
+                c if c == zero => 0,
+                c if c == one => 1,
+                _ => return Err(Error::parse_at(1, pass, &pass[offset..offset + c.len_utf8()], format!("expected {} or {}", zero, one))),
+            };
+            *value = (*value << 1) | bit;
+        }
+        if pass.chars().count() != row_bits + column_bits {
+            return Err(Error::parse_at(1, pass, pass, format!("expected a {} letter boarding pass", row_bits + column_bits)))
         }
-        seat_range
+        Ok(BoardingPass { row, col })
     }
 
-    fn id(&self) -> usize {
-        (self.row * 8) + self.col
+    /// The seat with the given id, None if the id is past the last seat.
+    pub fn from_id(id: usize) -> Option<BoardingPass> {
+        BoardingPass::from_id_with(id, &Geometry::default())
     }
-}
\ No newline at end of file
+
+    /// The seat with the given id on the given plane, None if no seat has it.
+    pub fn from_id_with(id: usize, geometry: &Geometry) -> Option<BoardingPass> {
+        geometry.seat(id).map(|(row, col)| BoardingPass { row, col })
+    }
+
+    /// The seat id, row * 8 + column.
+    pub fn id(&self) -> usize {
+        self.id_with(&Geometry::default())
+    }
+
+    /// The seat id on the given plane.
+    pub fn id_with(&self, geometry: &Geometry) -> usize {
+        geometry.id(self.row, self.col)
+    }
+
+    /// Encodes the seat back into its boarding pass, the reverse of decode. None if the seat isn't on the puzzle's plane.
+    pub fn encode(&self) -> Option<String> {
+        self.encode_with(&Geometry::default())
+    }
+
+    /// Encodes the seat into a boarding pass for the given plane, None if its row or column is past the plane's edge
+    /// (so it can't be encoded without losing bits).
+    pub fn encode_with(&self, geometry: &Geometry) -> Option<String> {
+        if self.row >= geometry.rows || self.col >= geometry.columns {
+            return None
+        }
+        let bits = |value: usize, count: usize, (zero, one): (char, char)| -> String {
+            (0..count).rev().map(|bit| if value & (1 << bit) == 0 { zero } else { one }).collect()
+        };
+        Some(bits(self.row, geometry.row_bits(), geometry.row_letters) + &bits(self.col, geometry.column_bits(), geometry.column_letters))
+    }
+}
+
+impl FromStr for BoardingPass {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<BoardingPass, Error> {
+        BoardingPass::decode(s)
+    }
+}
+
+impl fmt::Display for BoardingPass {
+    /// Writes the seat as its boarding pass, or its row and column if it isn't on the puzzle's plane.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.encode() {
+            Some(pass) => write!(f, "{}", pass),
+            None => write!(f, "row {} column {}", self.row, self.col),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoardingPass, Geometry, IdFormula};
+
+    #[test]
+    fn decodes_the_example() {
+        let pass = BoardingPass::decode("FBFBBFFRLR").unwrap();
+        assert_eq!((pass.row, pass.col, pass.id()), (44, 5, 357));
+        assert_eq!(BoardingPass::decode("BFFFBBFRRR").unwrap().id(), 567);
+        assert_eq!(BoardingPass::decode("FFFBBBFRRR").unwrap().id(), 119);
+        assert_eq!(BoardingPass::decode("BBFFBBFRLL").unwrap().id(), 820);
+    }
+
+    #[test]
+    fn every_id_round_trips() {
+        for id in 0..1024 {
+            let pass = BoardingPass::from_id(id).unwrap();
+            assert_eq!(pass.id(), id);
+            let encoded = pass.encode().unwrap();
+            assert_eq!(BoardingPass::decode(&encoded).unwrap(), pass, "{}", encoded);
+            assert_eq!(pass.to_string(), encoded);
+        }
+        assert_eq!(BoardingPass::from_id(1024), None);
+    }
+
+    #[test]
+    fn rejects_bad_letters() {
+        for pass in ["FBFBBFFRLX", "XBFBBFFRLR", "FBFBBFRRLR", "FBFBBFFFLR", "fbfbbffrlr", "FBFBBFFRL\u{e9}"] {
+            assert!(BoardingPass::decode(pass).is_err(), "{} should be rejected", pass);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_lengths() {
+        for pass in ["", "FBFBBFFRL", "FBFBBFFRLRR", "FBFBBFF", "FBFBBFFRLR "] {
+            assert!(BoardingPass::decode(pass).is_err(), "'{}' should be rejected", pass);
+        }
+    }
+
+    #[test]
+    fn seats_off_the_plane_dont_encode() {
+        assert_eq!(BoardingPass { row: 200, col: 0 }.encode(), None);
+        assert_eq!(BoardingPass { row: 0, col: 8 }.encode(), None);
+
+        let small = Geometry::new(4, 2, ('U', 'D'), ('<', '>')).unwrap();
+        let pass = BoardingPass::decode("FBFBBFFRLR").unwrap();
+        assert_eq!(pass.encode_with(&small), None);
+        let pass = BoardingPass::decode_with("DU>", &small).unwrap();
+        assert_eq!((pass.row, pass.col), (2, 1));
+        assert_eq!(pass.encode_with(&small).as_deref(), Some("DU>"));
+    }
+
+    // Checks every seat of the plane comes back from its id and its boarding pass.
+    fn assert_round_trips(geometry: &Geometry) {
+        for row in 0..geometry.rows() {
+            for col in 0..geometry.columns() {
+                let pass = BoardingPass { row, col };
+                let id = pass.id_with(geometry);
+                assert_eq!(BoardingPass::from_id_with(id, geometry), Some(pass), "id {}", id);
+                let encoded = pass.encode_with(geometry).unwrap();
+                assert_eq!(encoded.chars().count(), geometry.row_bits() + geometry.column_bits());
+                assert_eq!(BoardingPass::decode_with(&encoded, geometry).unwrap(), pass, "{}", encoded);
+            }
+        }
+    }
+
+    #[test]
+    fn column_major_ids_round_trip() {
+        let geometry = Geometry::new(16, 4, ('A', 'Z'), ('<', '>')).unwrap().with_id(IdFormula::ColumnMajor);
+        assert_round_trips(&geometry);
+        assert_eq!(BoardingPass { row: 3, col: 2 }.id_with(&geometry), 35);
+        assert_eq!(BoardingPass::from_id_with(64, &geometry), None);
+    }
+
+    #[test]
+    fn custom_ids_are_searched_for() {
+        let geometry = Geometry::new(8, 4, ('a', 'b'), ('x', 'y')).unwrap().with_id(IdFormula::Custom(|row, col| row * 100 + col));
+        assert_round_trips(&geometry);
+        assert_eq!(BoardingPass::from_id_with(503, &geometry), Some(BoardingPass { row: 5, col: 3 }));
+        // Ids the formula never gives, inside and past the range of the ones it does.
+        assert_eq!(BoardingPass::from_id_with(4, &geometry), None);
+        assert_eq!(BoardingPass::from_id_with(800, &geometry), None);
+    }
+
+    #[test]
+    fn other_letters() {
+        let geometry = Geometry::new(4, 2, ('a', 'b'), ('0', '1')).unwrap();
+        let pass = BoardingPass::decode_with("ba1", &geometry).unwrap();
+        assert_eq!((pass.row, pass.col, pass.id_with(&geometry)), (2, 1, 5));
+        assert!(BoardingPass::decode_with("BF1", &geometry).is_err());
+        assert!(BoardingPass::decode_with("baR", &geometry).is_err());
+        assert!(BoardingPass::decode_with("1ba", &geometry).is_err());
+    }
+
+    #[test]
+    fn bad_geometries() {
+        for size in [0, 3, 6, 100] {
+            assert!(Geometry::new(size, 8, ('F', 'B'), ('L', 'R')).is_none(), "{} rows", size);
+            assert!(Geometry::new(128, size, ('F', 'B'), ('L', 'R')).is_none(), "{} columns", size);
+        }
+        assert!(Geometry::new(128, 8, ('F', 'F'), ('L', 'R')).is_none());
+        assert!(Geometry::new(128, 8, ('F', 'B'), ('R', 'R')).is_none());
+
+        // A single row or column is fine, it just takes no letters.
+        let geometry = Geometry::new(1, 2, ('F', 'B'), ('L', 'R')).unwrap();
+        assert_eq!(BoardingPass { row: 0, col: 1 }.encode_with(&geometry).as_deref(), Some("R"));
+    }
+}
@@ -1,41 +1,80 @@
-/// Advent of Code Day 6
-/// 
-/// Takes an input of multiple groupings of strings of up to 26 letters a-z, strings are separated by \n in groupings, and groupings are separated by \n\n.
-///
-/// Both parts are split up into a few different iterators for readability, could chain them all together if you wanted.
-
-use std::io::Error;
-use std::fs;
+//! Advent of Code Day 6
+//! 
+//! Takes an input of multiple groupings of strings of up to 26 letters a-z, strings are separated by \n in groupings, and groupings are separated by \n\n.
+//!
+//! Both parts are split up into a few different iterators for readability, could chain them all together if you wanted.
 
 use std::collections::HashSet;
+use std::hash::Hash;
+
+use crate::error::Error;
+use crate::solution::{Answer, Solution};
+
+
+/// Day 6 solution.
+pub struct Day6;
+
+impl Solution for Day6 {
+    /// Each group, holding the set of letters for each person in it.
+    type Parsed = Vec<Vec<HashSet<char>>>;
+
+    fn day(&self) -> usize {
+        6
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Error> {
+        let mut groups = Vec::new();
+        let mut current: Vec<HashSet<char>> = Vec::new();
 
+        // Groups are separated by blank lines, every other line is one person's answers as letters a-z.
+        for (i, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                if !current.is_empty() {
+                    groups.push(std::mem::take(&mut current));
+                }
+                continue;
+            }
+            if let Some((col, c)) = line.chars().enumerate().find(|(_, c)| !c.is_ascii_lowercase()) {
+                return Err(Error::parse(i + 1, col + 1, c.to_string(), "expected a letter from a to z"))
+            }
+            current.push(line.chars().collect());
+        }
+        if !current.is_empty() {
+            groups.push(current);
+        }
+        Ok(groups)
+    }
 
-/// Day 6 Part 1 needs to find how many unique letters are in each grouping of strings, each one only counting once.
-pub fn part_one() -> Result<usize, Error> {
-    let input = fs::read_to_string("./day6_input.txt")?;
+    /// Day 6 Part 1 needs to find how many unique letters are in each grouping of strings, each one only counting once.
+    fn part_one(&self, groups: &Self::Parsed) -> Result<Answer, Error> {
 
-    let lines: Vec<HashSet<char>> = input.split("\n\n").map(|l| {
-        l.chars().filter(|c| *c != '\n').collect()
-    }).collect();
+        let lines: Vec<HashSet<char>> = groups.iter().map(|g| union(g)).collect();
 
-    let total = lines.iter().fold(0, |tot, l| tot + l.len());
-    Ok(total)
+        let total = lines.iter().fold(0, |tot, l| tot + l.len());
+        Ok(total.into())
+    }
+
+    /// Day 6 Part 2 needs to identify which questions everyone answered yes to (all strings in group must share letter).
+    fn part_two(&self, groups: &Self::Parsed) -> Result<Answer, Error> {
+
+        let lines: Vec<HashSet<char>> = groups.iter().map(|g| intersection(g)).collect();
+
+        let total = lines.iter().fold(0, |tot, l| tot + l.len());
+        Ok(total.into())
+    }
 }
 
-/// Day 6 Part 2 needs to identify which questions everyone answered yes to (all strings in group must share letter).
-pub fn part_two() -> Result<usize, Error> {
-    let input = fs::read_to_string("./day6_input.txt")?;
-
-    let lines: Vec<HashSet<char>> = input.split("\n\n").filter_map(|l| {
-        l.split("\n")
-            .map(|ln| {
-                ln.chars().collect()
-            })
-            .fold_first(|t, hs: HashSet<char>| {
-                t.intersection(&hs).cloned().collect()
-            })
-    }).collect();
-
-    let total = lines.iter().fold(0, |tot, l| tot + l.len());
-    Ok(total)
-}
\ No newline at end of file
+/// Returns every element that's in at least one of the given sets.
+pub fn union<T: Eq + Hash + Clone>(sets: &[HashSet<T>]) -> HashSet<T> {
+    sets.iter().flatten().cloned().collect()
+}
+
+/// Returns the elements that are in all of the given sets, or an empty set if there aren't any sets.
+pub fn intersection<T: Eq + Hash + Clone>(sets: &[HashSet<T>]) -> HashSet<T> {
+    sets.iter()
+        .cloned()
+        .reduce(|t, hs| {
+            t.intersection(&hs).cloned().collect()
+        })
+        .unwrap_or_default()
+}
@@ -1,72 +1,89 @@
-/// Advent of Code Day 7
-/// 
-/// Day 7 input is a list of lines in the format "{color} bags contain x {color2} bag, x {color3} bag, etc"
-/// 
-/// My solution for this is a huge mess of linked Rc<RefCells>>, was fun to write though.
-
-use std::io::Error;
-use std::fs;
+//! Advent of Code Day 7
+//! 
+//! Day 7 input is a list of lines in the format "{color} bags contain x {color2} bag, x {color3} bag, etc"
+//! 
+//! My solution for this is a huge mess of linked Rc<RefCells>>, was fun to write though.
 
 use std::collections::HashMap;
 use std::rc::Rc;
 use std::cell::RefCell;
 
-/// Day 7 Part 2 problem is finding how many bags can eventually contain at least one shiny gold bag.
-pub fn part_one() -> Result<usize, Error> {
-    let input = fs::read_to_string("./day7_input.txt")?;
-    let lines: Vec<&str> = input.split("\n").collect();
+use crate::error::Error;
+use crate::solution::{Answer, Solution};
 
-    let bags_map = build_bag_map_from_input(lines);
-    Ok(bags_map.values().filter(|b| b.borrow().holds_gold()).count())
-}
+/// Day 7 solution.
+pub struct Day7;
+
+impl Solution for Day7 {
+    /// Map of {bag_color: Bag}.
+    type Parsed = HashMap<String, Rc<RefCell<Bag>>>;
+
+    fn day(&self) -> usize {
+        7
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Error> {
+        let lines: Vec<&str> = input.lines().collect();
+        build_bag_map_from_input(lines)
+    }
 
-/// Day 7 Part 2 needs to figure out how many bags your shiny gold bag can hold. (all nested bags)
-pub fn part_two() -> Result<usize, Error> {
-    let input = fs::read_to_string("./day7_input.txt")?;
-    let lines: Vec<&str> = input.split("\n").collect();
+    /// Day 7 Part 2 problem is finding how many bags can eventually contain at least one shiny gold bag.
+    fn part_one(&self, bags_map: &Self::Parsed) -> Result<Answer, Error> {
+        Ok(bags_map.values().filter(|b| b.borrow().holds_gold()).count().into())
+    }
 
-    
-    let bags_map = build_bag_map_from_input(lines);
-    let holds = bags_map.get("shiny gold").unwrap().borrow().holds_how_many();
-    Ok(holds) 
+    /// Day 7 Part 2 needs to figure out how many bags your shiny gold bag can hold. (all nested bags)
+    fn part_two(&self, bags_map: &Self::Parsed) -> Result<Answer, Error> {
+        let holds = bags_map.get("shiny gold").ok_or(Error::NoSolution)?.borrow().holds_how_many();
+        Ok(holds.into())
+    }
 }
 
 
-// Helper function for day 7 problem, builds a map of {bag_color: Bag}, where each bag contains whatever other bags it holds and the amount
-fn build_bag_map_from_input(lines: Vec<&str>) -> HashMap<String, Rc<RefCell<Bag>>> {
+/// Helper function for day 7 problem, builds a map of {bag_color: Bag}, where each bag contains whatever other bags it holds and the amount
+/// Returns a parse error for any line that isn't in the expected format, or that makes a bag end up inside itself (which
+/// would leave it holding infinitely many bags).
+pub fn build_bag_map_from_input(lines: Vec<&str>) -> Result<HashMap<String, Rc<RefCell<Bag>>>, Error> {
 
     // Create a new bag map which holds {bag_color: Bag}
     let mut bags_map: HashMap<String, Rc<RefCell<Bag>>> = HashMap::new();
 
     // Iterate each line of bag information, it will be in the format of "{color} bags contain {x} {color} bags, etc, etc."
-    for line in lines {
+    for (line_number, full_line) in lines.iter().enumerate().map(|(i, l)| (i + 1, *l)) {
 
-        // Remove dots from line, and split it at contain to separate the current bag color, and the bags it contains.
-        let line = line.replace(".", "");
-        let split: Vec<&str> = line.split("contain").collect();
+        // Remove the trailing dot from line, and split it at contain to separate the current bag color, and the bags it contains.
+        let line = full_line.trim_end().trim_end_matches('.');
+        let (outer, inner) = line.split_once("contain")
+            .ok_or_else(|| Error::parse_at(line_number, full_line, line, "expected '{color} bags contain ...'"))?;
 
-        // Parse color of current bag out of split[1].
-        let color: String = split[0].split(" ").filter(|s| s.len() > 0 && *s != "bags").fold(String::new(), |w, s| w + s + " ");
+        // Parse color of current bag out of the part before contain.
+        let color: String = outer.split(" ").filter(|s| !s.is_empty() && *s != "bags").fold(String::new(), |w, s| w + s + " ");
         let color = color.trim_end().to_string();
+        if color.is_empty() {
+            return Err(Error::parse_at(line_number, full_line, outer, "expected a bag color"))
+        }
 
-        // Parse the color of bags current bag holds, as well as the count. Filters out bags which don't contain any others. Returns a Vec of (bag_color, count).
-        let holds: Vec<&str> = split[1].split(",").collect();
-        let holds: Vec<(String, usize)> = holds.iter().map(|s| {
-            let (mut color, mut count) = (String::new(), 0);
-            for i in s.split(" ").filter(|s| s.len() > 0 && *s != "bags" && *s != "bag") {
-                if let Ok(v) = i.parse::<usize>() {
-                    count = v;
-                } else {
-                    color = color + " " + i;
-                }
+        // Parse the color of bags current bag holds, as well as the count. Skips bags which don't contain any others. Returns a Vec of (bag_color, count, text).
+        let mut holds: Vec<(String, usize, &str)> = Vec::new();
+        for s in inner.split(",").map(|s| s.trim()) {
+            if s == "no other bags" {
+                continue;
+            }
+            let mut words = s.split(" ").filter(|s| !s.is_empty() && *s != "bags" && *s != "bag");
+            let count = match words.next().map(|w| w.parse::<usize>()) {
+                Some(Ok(v)) => v,
+                _ => return Err(Error::parse_at(line_number, full_line, s, "expected '{count} {color} bags'")),
+            };
+            let color = words.collect::<Vec<&str>>().join(" ");
+            if color.is_empty() {
+                return Err(Error::parse_at(line_number, full_line, s, "expected a bag color after the count"))
             }
-            let color = color.trim().to_string();
-            (color, count)
-        }).filter(|b| b.0 != "no other").collect();
+            holds.push((color, count, s));
+        }
 
 
         // If the current main color bag doesn't exist in our bags_map, create it.
-        if bags_map.get(&color).is_none() {
+        if !bags_map.contains_key(&color) {
             bags_map.insert(
                 color.clone(),
                 Rc::new(RefCell::new(Bag::from(color.clone())))
@@ -77,7 +94,7 @@ fn build_bag_map_from_input(lines: Vec<&str>) -> HashMap<String, Rc<RefCell<Bag>
         for hb in holds {
             
             // Get the &Rc to the current bag. If it the current bag doesn't exist create it.
-            if bags_map.get(&hb.0).is_none() {
+            if !bags_map.contains_key(&hb.0) {
                 bags_map.insert(
                     hb.0.clone(), 
                     Rc::new(RefCell::new(Bag::from(hb.0.clone())))
@@ -85,6 +102,11 @@ fn build_bag_map_from_input(lines: Vec<&str>) -> HashMap<String, Rc<RefCell<Bag>
             }
             let bag = bags_map.get(&hb.0).unwrap();
 
+            // The bags so far never hold themselves, so this can't loop forever, and checking every new one keeps it that way.
+            if hb.0 == color || bag.borrow().holds_bag(&color) {
+                return Err(Error::parse_at(line_number, full_line, hb.2, format!("a {} bag would end up inside itself", color)))
+            }
+
             // Add the current 'hold bag' to the actual bag we are iterating.
             bags_map.get(&color).unwrap()
                     .borrow_mut()
@@ -93,39 +115,62 @@ fn build_bag_map_from_input(lines: Vec<&str>) -> HashMap<String, Rc<RefCell<Bag>
         }
   
     }
-    bags_map
+    Ok(bags_map)
 }
 
+/// A bag in the bag graph, holding {bag_color: (count, Bag)} for each bag it directly contains.
 #[derive(Debug)]
-struct Bag {
-    color: String,
-    holds: HashMap<String, (usize, Rc<RefCell<Bag>>)>
+pub struct Bag {
+    pub color: String,
+    pub holds: HashMap<String, (usize, Rc<RefCell<Bag>>)>
 }
 
 
 impl Bag {
-    fn from(color: String) -> Bag {
+    pub fn from(color: String) -> Bag {
         Bag {
             color,
             holds: HashMap::new()
         }
     }
 
-    // If this bag holds gold directly, or if any bag it holds holds gold, return true.
-    fn holds_gold(&self) -> bool {
-        if !self.holds.get("shiny gold").is_none() {
-            return true
-        }
-        if self.holds.values().map(|x| {x.1.borrow().holds_gold()}).any(|x|x) {
+    /// If this bag holds gold directly, or if any bag it holds holds gold, return true.
+    pub fn holds_gold(&self) -> bool {
+        self.holds_bag("shiny gold")
+    }
+
+    /// If this bag holds a bag of the given color directly, or if any bag it holds does, return true.
+    pub fn holds_bag(&self, color: &str) -> bool {
+        if self.holds.contains_key(color) {
             return true
         }
-        return false
+        self.holds.values().any(|x| x.1.borrow().holds_bag(color))
     }
 
-    // Returns the amount of bags this bag holds (includes nested bag capacities).
-    fn holds_how_many(&self) -> usize {
+    /// Returns the amount of bags this bag holds (includes nested bag capacities).
+    pub fn holds_how_many(&self) -> usize {
         self.holds.values().map(|b| {
             (b.0 * b.1.borrow().holds_how_many()) + b.0
         }).sum()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::build_bag_map_from_input;
+    use crate::error::Error;
+
+    #[test]
+    fn bags_inside_themselves_are_parse_errors() {
+        let cycle = vec!["shiny gold bags contain 1 dark red bag.", "dark red bags contain 1 shiny gold bag."];
+        assert!(matches!(build_bag_map_from_input(cycle), Err(Error::Parse { line: 2, .. })));
+
+        let own = vec!["light red bags contain 2 light red bags."];
+        assert!(matches!(build_bag_map_from_input(own), Err(Error::Parse { line: 1, .. })));
+
+        let fine = vec!["shiny gold bags contain 2 dark red bags.", "dark red bags contain no other bags.", "light red bags contain 1 shiny gold bag, 1 dark red bag."];
+        let bags = build_bag_map_from_input(fine).unwrap();
+        assert!(bags["light red"].borrow().holds_gold());
+        assert_eq!(bags["shiny gold"].borrow().holds_how_many(), 2);
+    }
+}
@@ -1,83 +1,103 @@
-/// Advent of Code Day 8
-/// 
-/// Day 8 takes an input of multiple lines with a command on each in the format of {command} {+ || -}{int}. The command can either be nop (no operation), acc (add to accumulator), or jmp (jump to instruction).
-/// The given instructions will be an infinite loop.
-
-use std::io::Error;
-use std::fs;
-
-
-// Day 8 Part 1 problem is to find the value of acc before the first instance of any instruction being run twice.
-pub fn part_one() -> Result<isize, Error> {
-    let input = fs::read_to_string("./day8_input.txt")?;
-    let instructions: Vec<(&str, isize, usize)> = input.split("\n").filter_map(|l| {
-        let mut split = l.split(" ");
-        if let (Some(instr), Some(int)) = (split.next(), split.next()) {
-            match int.parse::<isize>() {
-                Ok(int) => Some((instr, int, 0)),
-                Err(_) => None
-            }
-        } else {
-            None
-        }
-    }).collect();
+//! Advent of Code Day 8
+//! 
+//! Day 8 takes an input of multiple lines with a command on each in the format of {command} {+ || -}{int}. The command can either be nop (no operation), acc (add to accumulator), or jmp (jump to instruction).
+//! The given instructions will be an infinite loop.
+
+use crate::error::Error;
+use crate::solution::{Answer, Solution};
+
+
+/// Day 8 solution.
+pub struct Day8;
 
-    match run_instructions(instructions) {
-        InstructionResult::Loops(val) => return Ok(val),
-        _ => {Ok(0)},
+impl Solution for Day8 {
+    type Parsed = Vec<Instruction>;
+
+    fn day(&self) -> usize {
+        8
     }
-}
 
-/// Day 8 Part 2 problem is finding the value of the accumulator when the program terminates after changing one nop->jmp or jmp->nop which fixes the infinite loop in the program.
-pub fn part_two() -> Result<isize, Error> {
-    let input = fs::read_to_string("./day8_input.txt")?;
-    let instructions: Vec<(&str, isize, usize)> = input.split("\n").filter_map(|l| {
-        let mut split = l.split(" ");
-        if let (Some(instr), Some(int)) = (split.next(), split.next()) {
-            match int.parse::<isize>() {
-                Ok(int) => Some((instr, int, 0)),
-                Err(_) => None
+    fn parse(&self, input: &str) -> Result<Vec<Instruction>, Error> {
+        input.lines().enumerate().map(|(i, l)| {
+            let mut split = l.split(" ");
+            if let (Some(instr), Some(int), None) = (split.next(), split.next(), split.next()) {
+                let op = match instr {
+                    "acc" => Op::Acc,
+                    "jmp" => Op::Jmp,
+                    "nop" => Op::Nop,
+                    _ => return Err(Error::parse_at(i + 1, l, instr, "expected acc, jmp or nop")),
+                };
+                match int.parse::<isize>() {
+                    Ok(arg) => Ok(Instruction { op, arg }),
+                    Err(_) => Err(Error::parse_at(i + 1, l, int, "expected a signed number"))
+                }
+            } else {
+                Err(Error::parse_at(i + 1, l, l, "expected '{op} {arg}'"))
             }
-        } else {
-            None
+        }).collect()
+    }
+
+    // Day 8 Part 1 problem is to find the value of acc before the first instance of any instruction being run twice.
+    fn part_one(&self, instructions: &Vec<Instruction>) -> Result<Answer, Error> {
+        match run_instructions(instructions) {
+            InstructionResult::Loops(val) => Ok(val.into()),
+            _ => Err(Error::NoSolution),
         }
-    }).collect();
-    
-    // Iterate each instruction, if it's nop/jmp flip it and see if the program terminates.
-    for (i, instr) in instructions.iter().enumerate() {
+    }
+
+    /// Day 8 Part 2 problem is finding the value of the accumulator when the program terminates after changing one nop->jmp or jmp->nop which fixes the infinite loop in the program.
+    fn part_two(&self, instructions: &Vec<Instruction>) -> Result<Answer, Error> {
+
+        // Iterate each instruction, if it's nop/jmp flip it and see if the program terminates.
+        for (i, instr) in instructions.iter().enumerate() {
 
-        // If instruction is nop or jmp, flip it's instruction and test to see if the program terminates.
-        if ["nop", "jmp"].contains(&instr.0) {
+            // If instruction is nop or jmp, flip it's instruction and test to see if the program terminates.
+            let flipped = match instr.op {
+                Op::Nop => Op::Jmp,
+                Op::Jmp => Op::Nop,
+                Op::Acc => continue,
+            };
             let mut new_instructions = instructions.clone();
-            new_instructions[i] = (
-                match instr.0 {
-                    "nop" => "jmp",
-                    "jmp" => "nop",
-                    _ => {""}
-                },
-                instr.1,
-                0
-            );
-            if let InstructionResult::Terminates(val) = run_instructions(new_instructions) {
-                return Ok(val)
+            new_instructions[i].op = flipped;
+            if let InstructionResult::Terminates(val) = run_instructions(&new_instructions) {
+                return Ok(val.into())
             }
         }
-        
+        Err(Error::NoSolution)
     }
-    Ok(0)
 }
 
-enum InstructionResult {
+/// The operation of a single instruction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Acc,
+    Jmp,
+    Nop,
+}
+
+/// A single instruction from the program, {op} {arg}.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Instruction {
+    pub op: Op,
+    pub arg: isize,
+}
+
+/// How a program run ended, along with the accumulator value at that point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InstructionResult {
     Loops(isize),
     Terminates(isize),
     Errors
 }
 
-// Run given set of instructions, return if it terminates or loops, as well as the accumulator result when it does.
-fn run_instructions(mut instructions: Vec<(&str, isize, usize)>) -> InstructionResult {
+/// Run given set of instructions, return if it terminates or loops, as well as the accumulator result when it does.
+pub fn run_instructions(instructions: &[Instruction]) -> InstructionResult {
     let mut accumulator: isize = 0;
     let mut index: isize = 0;
 
+    // How many times each instruction has been run.
+    let mut run_counts = vec![0; instructions.len()];
+
     // When index > len of instructions, program has successfully terminated.
     while index < instructions.len() as isize {
         
@@ -86,38 +106,38 @@ fn run_instructions(mut instructions: Vec<(&str, isize, usize)>) -> InstructionR
             return InstructionResult::Errors
         }
 
-        // Match current instruction, instructions are in the format (instruction, int, count)
-        if let Some(val) = instructions.get_mut(index as usize) {
+        // Match current instruction along with how many times it has been run.
+        if let (Some(instr), Some(count)) = (instructions.get(index as usize), run_counts.get_mut(index as usize)) {
 
-            match val {
+            match (instr.op, instr.arg, *count) {
 
                 // Acc instruction adds the value to the accumulator total
-                ("acc", i, 0) => {
-                    accumulator += *i;
+                (Op::Acc, i, 0) => {
+                    accumulator += i;
                     index += 1;
                 },
 
                 // Jmp jumps forward i (isize) number of instructions.
-                ("jmp", i, 0) => {
+                (Op::Jmp, i, 0) => {
                     // catch jmp 0 loops
-                    if *i == 0 {
+                    if i == 0 {
                         return InstructionResult::Loops(accumulator)
                     }
-                    index += *i;
+                    index += i;
                 },
 
                 // Nop is no operation, just move to next instruction.
-                ("nop", _, 0) => {
+                (Op::Nop, _, 0) => {
                     index += 1;
                 },
 
-                // Break on first instance of .2 being > 0 (meaning an instruction has already been run)
+                // Break on first instance of count being > 0 (meaning an instruction has already been run)
                 (_,_,_) => {
                     return InstructionResult::Loops(accumulator)
                 }
             }
             // Increment instruction call count.
-            val.2 += 1;
+            *count += 1;
         } else {return InstructionResult::Errors}
 
     }
@@ -1,15 +1,63 @@
-/// Advent of Code Day 9
-/// 
-/// Day 9 takes a list of numbers starting with a 25 count preamble, after that each number should bet he sum of any two of the 25 previous numbers.
+//! Advent of Code Day 9
+//! 
+//! Day 9 takes a list of numbers starting with a 25 count preamble, after that each number should bet he sum of any two of the 25 previous numbers.
 
-use std::io::Error;
-use std::fs;
+use crate::error::Error;
+use crate::solution::{Answer, Solution};
 
 
-/// Part 1 is finding the first number in the input list that doesn't follow the rule.
-pub fn part_one() -> Result<Option<usize>, Error> {
-    let input = fs::read_to_string("./day9_input.txt")?;
-    let vals: Vec<usize> = input.split("\n").filter_map(|v| {if let Ok(val) = v.parse::<usize>(){Some(val)}  else {None}}).collect();
+/// Day 9 solution.
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Parsed = Vec<usize>;
+
+    fn day(&self) -> usize {
+        9
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<usize>, Error> {
+        input.lines().enumerate().map(|(i, l)| {
+            l.trim().parse::<usize>().map_err(|_| Error::parse_at(i + 1, l, l.trim(), "expected a number"))
+        }).collect()
+    }
+
+    /// Part 1 is finding the first number in the input list that doesn't follow the rule.
+    fn part_one(&self, vals: &Vec<usize>) -> Result<Answer, Error> {
+        find_invalid_number(vals).map(Answer::from).ok_or(Error::NoSolution)
+    }
+
+    /// Part 2 is finding a contiguous set of at least two numbers which add up to the number from part 1, then taking the smallest and largest number in that range and summing them.
+    fn part_two(&self, vals: &Vec<usize>) -> Result<Answer, Error> {
+
+        // Get our invalid number from part 1.
+        let invalid_number = find_invalid_number(vals).ok_or(Error::NoSolution)?;
+
+        // Iterate values to find a contiguous set adding up to invalid_number. Skip any values > than invalid number.
+        for (i, v) in vals.iter().enumerate().filter(|(_, v)| {**v < invalid_number}) {
+
+            let mut current_set: Vec<usize> = vec![*v];
+
+            // Iterate all numbers after current index and add to current set to see if it matches the criteria.
+            for val in &vals[i+1..] {
+
+                let tot: usize = current_set.iter().sum();
+                if tot + val == invalid_number && current_set.len() >= 2{
+                    current_set.sort();
+                    return Ok((current_set[0] + current_set[current_set.len()-1]).into());
+                }
+                else if tot + val < invalid_number {
+                    current_set.push(*val);
+                } else {break}
+
+            }
+        }
+        Err(Error::NoSolution)
+    }
+}
+
+/// Finds the first number in the list that isn't the sum of two of the 25 numbers before it.
+pub fn find_invalid_number(vals: &[usize]) -> Option<usize> {
 
     // Iterate values to find the first one that doesn't follow the preamble rule.
     for (i, v) in vals.iter().enumerate().filter(|(i, _)| {*i >= 25}) {
@@ -26,41 +74,8 @@ pub fn part_one() -> Result<Option<usize>, Error> {
             }
         }
         if !matches_rule {
-            return Ok(Some(*v));
+            return Some(*v);
         }
     }
-    Ok(None)
+    None
 }
-
-/// Part 2 is finding a contiguous set of at least two numbers which add up to the number from part 1, then taking the smallest and largest number in that range and summing them.
-pub fn part_two() -> Result<Option<usize>, Error> {
-    let input = fs::read_to_string("./day9_input.txt")?;
-    let vals: Vec<usize> = input.split("\n").filter_map(|v| {if let Ok(val) = v.parse::<usize>(){Some(val)}  else {None}}).collect();
-
-    // Get our invalid number from part 1.
-    let invalid_number = match part_one()? {
-        Some(v) => v,
-        None => return Ok(None)
-    };
-
-    // Iterate values to find a contiguous set adding up to invalid_number. Skip any values > than invalid number.
-    for (i, v) in vals.iter().enumerate().filter(|(_, v)| {**v < invalid_number}) {
-        
-        let mut current_set: Vec<usize> = vec![v.clone()];
-
-        // Iterate all numbers after current index and add to current set to see if it matches the criteria.
-        for val in &vals[i+1..] {
-            
-            let tot: usize = current_set.iter().sum();
-            if tot + val == invalid_number && current_set.len() >= 2{
-                current_set.sort();
-                return Ok(Some(current_set[0] + current_set[current_set.len()-1]));
-            }
-            else if tot + val < invalid_number {
-                current_set.push(val.clone());
-            } else {break}
-                
-        }
-    }
-    Ok(None)
-}
\ No newline at end of file
@@ -0,0 +1,76 @@
+//! Crate wide error type, shared by every day.
+
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Reading the input failed.
+    Io(io::Error),
+
+    /// The input is malformed. Line and column are 1 based, text is the offending part of the line.
+    Parse {
+        line: usize,
+        column: usize,
+        text: String,
+        message: String,
+    },
+
+    /// The input was valid but doesn't contain an answer.
+    NoSolution,
+}
+
+impl Error {
+
+    /// Creates a parse error for the given position.
+    pub fn parse<T: Into<String>, M: Into<String>>(line: usize, column: usize, text: T, message: M) -> Error {
+        Error::Parse { line, column, text: text.into(), message: message.into() }
+    }
+
+    /// Creates a parse error for `part`, which must be a slice of `line`. The column is worked out from where part sits in line.
+    pub fn parse_at<M: Into<String>>(line_number: usize, line: &str, part: &str, message: M) -> Error {
+        Error::parse(line_number, column_of(line, part), part, message)
+    }
+
+    /// Moves a parse error to the given line, for parsers that only see a single line at a time. Other errors are unchanged.
+    pub fn at_line(self, line_number: usize) -> Error {
+        match self {
+            Error::Parse { column, text, message, .. } => Error::Parse { line: line_number, column, text, message },
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Parse { line, column, text, message } => write!(f, "line {}, column {}: {} ('{}')", line, column, message, text),
+            Error::NoSolution => write!(f, "no solution found in the input"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
+
+// 1 based character column of `part` within `line`. Falls back to 1 if part isn't actually a slice of line.
+fn column_of(line: &str, part: &str) -> usize {
+    let offset = (part.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+    match line.get(..offset) {
+        Some(before) if offset <= line.len() => before.chars().count() + 1,
+        _ => 1,
+    }
+}
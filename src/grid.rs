@@ -0,0 +1,255 @@
+//! A rectangular grid of cells, for the puzzles whose input is a map.
+//!
+//! Either axis can be made to wrap, so positions past the edge carry on from the other side (like day 3's map repeating
+//! to the right). Positions are (row, column) pairs starting from the top left.
+
+use std::convert::TryFrom;
+
+use crate::error::Error;
+
+/// Offsets of the 8 cells around a position, as (row, column).
+const NEIGHBOUR_OFFSETS: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+/// Offsets of the 4 cells directly above, left, right and below a position.
+const ADJACENT_OFFSETS: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+    wrap_rows: bool,
+    wrap_columns: bool,
+}
+
+impl<T> Grid<T> {
+
+    /// Builds a grid from its rows. Returns None if the rows aren't all the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let width = rows.first().map_or(0, |r| r.len());
+        if rows.iter().any(|r| r.len() != width) {
+            return None
+        }
+        let height = rows.len();
+        Some(Grid { cells: rows.into_iter().flatten().collect(), width, height, wrap_rows: false, wrap_columns: false })
+    }
+
+    /// Parses a grid with a character per cell and a line per row. `cell` turns a character into a cell, or None if it
+    /// isn't valid, in which case a parse error with the `expected` message is returned.
+    /// Every row must be the same width and the grid can't be empty.
+    pub fn parse<F: Fn(char) -> Option<T>>(input: &str, expected: &str, cell: F) -> Result<Grid<T>, Error> {
+        let lines: Vec<&str> = input.lines().collect();
+        let width = match lines.first() {
+            Some(l) if !l.is_empty() => l.chars().count(),
+            _ => return Err(Error::parse(1, 1, "", "the map is empty")),
+        };
+
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for (i, l) in lines.iter().enumerate() {
+            for (col, c) in l.chars().enumerate() {
+                cells.push(cell(c).ok_or_else(|| Error::parse(i + 1, col + 1, c.to_string(), expected))?);
+            }
+            if l.chars().count() != width {
+                return Err(Error::parse(i + 1, 1, *l, format!("expected a row {} wide", width)))
+            }
+        }
+        Ok(Grid { cells, width, height: lines.len(), wrap_rows: false, wrap_columns: false })
+    }
+
+    /// Sets which axes wrap around. With wrap_columns, going off the right edge comes back in on the left (and the other
+    /// way around), wrap_rows does the same for the top and bottom.
+    pub fn wrapping(mut self, wrap_rows: bool, wrap_columns: bool) -> Grid<T> {
+        self.wrap_rows = wrap_rows;
+        self.wrap_columns = wrap_columns;
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The cell at the given position, wrapping it onto the grid on the axes that wrap.
+    /// None if the position is off the edge of an axis that doesn't wrap.
+    pub fn get(&self, row: usize, column: usize) -> Option<&T> {
+        let (row, column) = self.offset(row, column, 0, 0)?;
+        self.cells.get(row * self.width + column)
+    }
+
+    /// Mutable version of get.
+    pub fn get_mut(&mut self, row: usize, column: usize) -> Option<&mut T> {
+        let (row, column) = self.offset(row, column, 0, 0)?;
+        self.cells.get_mut(row * self.width + column)
+    }
+
+    /// The position (on the grid) reached by moving from a position by the given row/column offsets, following
+    /// wrapping. None if it's off the edge of an axis that doesn't wrap, or too far out to work out (past isize::MAX).
+    pub fn offset(&self, row: usize, column: usize, row_offset: isize, column_offset: isize) -> Option<(usize, usize)> {
+        let row = isize::try_from(row).ok()?.checked_add(row_offset)?;
+        let column = isize::try_from(column).ok()?.checked_add(column_offset)?;
+        self.resolve(row, column)
+    }
+
+    /// The positions and cells of the (up to) 8 cells around a position, including diagonals.
+    ///
+    /// On a wrapping axis less than 3 long several of the offsets land on the same cell, or on the position itself.
+    /// Each cell is only returned once, and the position's own cell never is, so e.g. a 2x2 grid wrapping both ways
+    /// gives the other 3 cells.
+    pub fn neighbours(&self, row: usize, column: usize) -> impl Iterator<Item = ((usize, usize), &T)> + '_ {
+        self.around(row, column, &NEIGHBOUR_OFFSETS)
+    }
+
+    /// The positions and cells of the (up to) 4 cells directly above, left, right and below a position. Like neighbours,
+    /// each cell is only returned once and the position's own cell never is.
+    pub fn adjacent(&self, row: usize, column: usize) -> impl Iterator<Item = ((usize, usize), &T)> + '_ {
+        self.around(row, column, &ADJACENT_OFFSETS)
+    }
+
+    /// A single row, None if it's past the bottom of the grid. Rows don't wrap here.
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        if row < self.height {
+            Some(&self.cells[row * self.width..(row + 1) * self.width])
+        } else {
+            None
+        }
+    }
+
+    /// Every row from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on 0, a grid with no columns has no cells so any size gives no rows.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The cells of a single column from top to bottom, empty if it's past the right edge. Columns don't wrap here.
+    pub fn column(&self, column: usize) -> impl Iterator<Item = &T> {
+        let cells = if column < self.width { &self.cells[column..] } else { &[] };
+        cells.iter().step_by(self.width.max(1))
+    }
+
+    /// Every column from left to right.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |c| self.column(c))
+    }
+
+    // Moves a possibly off grid position onto the grid along the wrapping axes, None if it's off a non wrapping one.
+    fn resolve(&self, row: isize, column: isize) -> Option<(usize, usize)> {
+        let row = wrap(row, self.height, self.wrap_rows)?;
+        let column = wrap(column, self.width, self.wrap_columns)?;
+        Some((row, column))
+    }
+
+    // The cells at each of the offsets from a position that are on the grid, skipping the position itself and any cell
+    // an earlier offset already reached (which wrapping on a small grid can cause).
+    fn around<'a>(&'a self, row: usize, column: usize, offsets: &'static [(isize, isize)]) -> impl Iterator<Item = ((usize, usize), &'a T)> + 'a {
+        let start = self.offset(row, column, 0, 0);
+        let mut positions: Vec<(usize, usize)> = Vec::with_capacity(offsets.len());
+        for (r, c) in offsets {
+            if let Some(position) = self.offset(row, column, *r, *c) {
+                if Some(position) != start && !positions.contains(&position) {
+                    positions.push(position);
+                }
+            }
+        }
+        positions.into_iter().filter_map(move |(r, c)| self.get(r, c).map(|cell| ((r, c), cell)))
+    }
+}
+
+// Brings a single coordinate into 0..size, wrapping it if the axis wraps.
+fn wrap(n: isize, size: usize, wraps: bool) -> Option<usize> {
+    if size == 0 {
+        None
+    } else if wraps {
+        Some(n.rem_euclid(size as isize) as usize)
+    } else if n >= 0 && (n as usize) < size {
+        Some(n as usize)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+
+    // A grid of the letters a, b, c... a row at a time.
+    fn letters(rows: usize, columns: usize) -> Grid<char> {
+        let rows = (0..rows).map(|r| (0..columns).map(|c| (b'a' + (r * columns + c) as u8) as char).collect()).collect();
+        Grid::from_rows(rows).unwrap()
+    }
+
+    fn cells<'a, I: Iterator<Item = ((usize, usize), &'a char)>>(around: I) -> String {
+        let mut cells: Vec<char> = around.map(|(_, c)| *c).collect();
+        cells.sort_unstable();
+        cells.into_iter().collect()
+    }
+
+    #[test]
+    fn neighbours_stop_at_the_edges() {
+        let grid = letters(3, 3);
+        assert_eq!(cells(grid.neighbours(1, 1)), "abcdfghi");
+        assert_eq!(cells(grid.neighbours(0, 0)), "bde");
+        assert_eq!(cells(grid.adjacent(1, 1)), "bdfh");
+        assert_eq!(cells(grid.adjacent(2, 2)), "fh");
+        assert_eq!(cells(grid.neighbours(5, 5)), "");
+    }
+
+    #[test]
+    fn neighbours_wrap() {
+        let grid = letters(3, 3).wrapping(true, true);
+        assert_eq!(cells(grid.neighbours(0, 0)), "bcdefghi");
+        assert_eq!(cells(grid.adjacent(0, 0)), "bcdg");
+        let positions: Vec<(usize, usize)> = grid.adjacent(0, 0).map(|(p, _)| p).collect();
+        assert_eq!(positions, vec![(2, 0), (0, 2), (0, 1), (1, 0)]);
+
+        let columns_only = letters(3, 3).wrapping(false, true);
+        assert_eq!(cells(columns_only.neighbours(0, 0)), "bcdef");
+    }
+
+    #[test]
+    fn small_wrapping_grids_give_each_neighbour_once() {
+        let grid = letters(2, 2).wrapping(true, true);
+        assert_eq!(cells(grid.neighbours(0, 0)), "bcd");
+        assert_eq!(cells(grid.adjacent(0, 0)), "bc");
+
+        let single = letters(1, 1).wrapping(true, true);
+        assert_eq!(cells(single.neighbours(0, 0)), "");
+
+        let strip = letters(1, 4).wrapping(true, true);
+        assert_eq!(cells(strip.neighbours(0, 0)), "bd");
+    }
+
+    #[test]
+    fn huge_positions_dont_wrap_around() {
+        let grid = letters(3, 3).wrapping(true, true);
+        assert_eq!(grid.get(usize::MAX, 0), None);
+        assert_eq!(grid.get(0, usize::MAX), None);
+        assert_eq!(grid.offset(0, 0, isize::MIN, 0), Some((1, 0)));
+        assert_eq!(grid.get(4, 5), Some(&'f'));
+        assert_eq!(letters(3, 3).get(3, 0), None);
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = letters(2, 3);
+        let rows: Vec<String> = grid.rows().map(|r| r.iter().collect()).collect();
+        assert_eq!(rows, vec!["abc", "def"]);
+        let columns: Vec<String> = grid.columns().map(|c| c.collect()).collect();
+        assert_eq!(columns, vec!["ad", "be", "cf"]);
+        assert_eq!(grid.row(1), Some(&['d', 'e', 'f'][..]));
+        assert_eq!(grid.row(2), None);
+        assert_eq!(grid.column(3).count(), 0);
+
+        // Wrapping only affects positions, rows and columns are still just the grid's own.
+        let wrapped = letters(2, 3).wrapping(true, true);
+        assert_eq!(wrapped.rows().count(), 2);
+        assert_eq!(wrapped.columns().count(), 3);
+        assert_eq!(wrapped.row(2), None);
+
+        let empty: Grid<char> = Grid::from_rows(Vec::new()).unwrap();
+        assert_eq!(empty.rows().count(), 0);
+        assert_eq!(empty.columns().count(), 0);
+    }
+}
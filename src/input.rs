@@ -0,0 +1,110 @@
+//! Where a day's puzzle input comes from.
+//!
+//! Solutions only ever see the input as a &str, this handles getting it from a file, stdin, or memory.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+
+/// Environment variable that overrides the directory day inputs are read from.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Input {
+    File(PathBuf),
+    Stdin,
+    Text(String),
+}
+
+impl Input {
+
+    /// The default input for a day inside the given directory (dir/dayN_input.txt).
+    pub fn for_day<P: AsRef<Path>>(dir: P, day: usize) -> Input {
+        Input::File(dir.as_ref().join(format!("day{}_input.txt", day)))
+    }
+
+    /// Reads the whole input. Trailing whitespace is removed so a final newline doesn't show up as an empty entry.
+    pub fn read(&self) -> Result<String, Error> {
+        let mut contents = match self {
+            Input::File(path) => fs::read_to_string(path).map_err(|e| self.read_error(e))?,
+            Input::Stdin => {
+                let mut buf = String::new();
+                io::stdin().read_to_string(&mut buf).map_err(|e| self.read_error(e))?;
+                buf
+            },
+            Input::Text(s) => s.clone(),
+        };
+        contents.truncate(contents.trim_end().len());
+        Ok(contents)
+    }
+
+    /// Opens the input for reading a bit at a time, for inputs too big to read in one go.
+    pub fn open(&self) -> Result<Box<dyn BufRead>, Error> {
+        Ok(match self {
+            Input::File(path) => Box::new(BufReader::new(fs::File::open(path).map_err(|e| self.read_error(e))?)),
+            Input::Stdin => Box::new(io::stdin().lock()),
+            Input::Text(s) => Box::new(Cursor::new(s.clone().into_bytes())),
+        })
+    }
+
+    // Adds where the input was being read from to an io error.
+    fn read_error(&self, e: io::Error) -> Error {
+        Error::Io(io::Error::new(e.kind(), format!("couldn't read input {}: {}", self, e)))
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Input::File(path) => write!(f, "{}", path.display()),
+            Input::Stdin => write!(f, "stdin"),
+            Input::Text(_) => write!(f, "in-memory input"),
+        }
+    }
+}
+
+impl From<&str> for Input {
+    fn from(s: &str) -> Input {
+        Input::Text(s.to_string())
+    }
+}
+
+/// Where the runner gets inputs from, either a directory of dayN_input.txt files or one input used for every day.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSource {
+    Dir(PathBuf),
+    Single(Input),
+}
+
+impl InputSource {
+
+    /// Builds the source from the --input argument: `-` is stdin, a directory is searched for each day's file,
+    /// and anything else is a single input file. Without the argument $AOC_INPUT_DIR or the current directory is used.
+    pub fn from_arg(arg: Option<&str>) -> InputSource {
+        match arg {
+            None => InputSource::Dir(default_dir()),
+            Some("-") => InputSource::Single(Input::Stdin),
+            Some(path) if Path::new(path).is_dir() => InputSource::Dir(PathBuf::from(path)),
+            Some(path) => InputSource::Single(Input::File(PathBuf::from(path))),
+        }
+    }
+
+    /// The input to use for the given day.
+    pub fn for_day(&self, day: usize) -> Input {
+        match self {
+            InputSource::Dir(dir) => Input::for_day(dir, day),
+            InputSource::Single(input) => input.clone(),
+        }
+    }
+}
+
+// Returns the directory day inputs should be read from, either $AOC_INPUT_DIR or the current directory.
+fn default_dir() -> PathBuf {
+    match std::env::var_os(INPUT_DIR_VAR) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from("."),
+    }
+}
@@ -0,0 +1,23 @@
+//! Advent of Code 2020 solutions as a library.
+//!
+//! Each `dayN` module holds that day's Solution along with its parser and helper types, e.g. day8's instruction VM,
+//! day7's bag graph or day5's seat decoding. `solution::registry()` lists every solved day.
+//! `grid::Grid` is a general map type for the puzzles with a grid of cells as their input, and `schema::Schema`
+//! describes the fields of key:value documents like day4's passports, with `measure` parsing lengths like their heights.
+
+pub mod error;
+pub mod grid;
+pub mod input;
+pub mod measure;
+pub mod schema;
+pub mod solution;
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
@@ -1,42 +1,275 @@
-#![feature(iterator_fold_self)]
+mod answers;
+mod cli;
+mod report;
 
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
+use std::io::ErrorKind;
+use std::time::Duration;
 
-fn main() -> Result<(), std::io::Error> {
+use advent_of_code::day2::{self, PolicyRule, RuleRegistry};
+use advent_of_code::day3::{self, Day3};
+use advent_of_code::day4::{self, Passport, PassportReader, ReadError};
+use advent_of_code::error::Error;
+use advent_of_code::input::InputSource;
+use advent_of_code::schema::Schema;
+use advent_of_code::solution::{self, DayRun, Runner, Solution};
 
-    println!("Day 1, Part 1 answer is {:?}", day1::part_one());
-    println!("Day 1, Part 2 answer is {:?}\n", day1::part_two());
+use answers::Answers;
+use cli::{AuditOptions, Command, DaySelection, Export, Format, PassportOptions, RunOptions, TrajectoryOptions, VerifyOptions};
 
-    println!("Day 2, Part 1 answer is {:?}", day2::part_one());
-    println!("Day 2, Part 2 answer is {:?}\n", day2::part_two());
+fn main() {
+    let command = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
 
-    println!("Day 3, Part 1 answer is {:?}", day3::part_one());
-    println!("Day 3, Part 2 answer is {:?}\n", day3::part_two());
+    let solutions = solution::registry();
 
-    println!("Day 4, Part 1 answer is {:?}", day4::part_one());
-    println!("Day 4, Part 2 answer is {:?}\n", day4::part_two());
+    match command {
+        Command::Help => println!("{}", cli::USAGE),
+        Command::List => {
+            for s in &solutions {
+                println!("Day {}: parts 1, 2", s.day());
+            }
+        },
+        Command::Run(options) => run(&solutions, options),
+        Command::Verify(options) => verify(&solutions, options),
+        Command::Audit(options) => audit(options),
+        Command::Trajectory(options) => trajectory(options),
+        Command::Passports(options) => passports(options),
+    }
+}
+
+// Runs the selected days and prints their answers and timings in the requested format, or the benchmark report.
+fn run(solutions: &[Box<dyn Runner>], options: RunOptions) {
+    let selected = select(solutions, &options.days, options.input.as_deref());
+    let parts: Vec<usize> = [1, 2].iter().cloned().filter(|p| options.part.is_none_or(|part| part == *p)).collect();
+    let runs = run_days(&selected, options.input.as_deref(), &parts, options.bench.unwrap_or(1));
+
+    if options.bench.is_some() {
+        report::print_bench(&runs);
+    } else {
+        let runs = last_runs(runs);
+        match options.format {
+            Format::Text => report::print_table(&runs),
+            Format::Json => report::print_json(&report::records(&runs, &parts)),
+            Format::Csv => report::print_csv(&report::records(&runs, &parts)),
+        }
+    }
+}
+
+// Runs the selected days and checks their answers against the answers file, exiting non-zero on any mismatch, error or
+// answer that has nothing stored to check it against. With --save the answers file is updated with the current answers
+// instead, creating it if it doesn't exist yet.
+fn verify(solutions: &[Box<dyn Runner>], options: VerifyOptions) {
+    let selected = select(solutions, &options.days, options.input.as_deref());
+    let path = options.answers.as_deref().unwrap_or(answers::DEFAULT_PATH);
+    let mut expected = match Answers::load(path) {
+        Ok(a) => a,
+        Err(e) if options.save && e.kind() == ErrorKind::NotFound => Answers::default(),
+        Err(e) => {
+            eprintln!("Couldn't load answers from {}: {}", path, e);
+            std::process::exit(2);
+        }
+    };
 
-    println!("Day 5, Part 1 answer is {:?}", day5::part_one());
-    println!("Day 5, Part 2 answer is {:?}\n", day5::part_two());
+    let runs = last_runs(run_days(&selected, options.input.as_deref(), &[1, 2], 1));
+    let (mut passed, mut failed, mut unchecked) = (0, 0, 0);
 
-    println!("Day 6, Part 1 answer is {:?}", day6::part_one());
-    println!("Day 6, Part 2 answer is {:?}\n", day6::part_two());
+    for (day, run) in &runs {
+        let part_runs = match &run.parts {
+            Ok(p) => p,
+            Err(e) => {
+                println!("Day {}: error: {}", day, e);
+                failed += 2;
+                continue;
+            }
+        };
+        for p in part_runs {
+            let label = format!("Day {} Part {}", day, p.part);
+            match (&p.answer, expected.get(*day, p.part)) {
+                (Err(Error::NoSolution), _) => {
+                    println!("{}: no answer", label);
+                    failed += 1;
+                },
+                (Err(e), _) => {
+                    println!("{}: error: {}", label, e);
+                    failed += 1;
+                },
+                (Ok(answer), _) if options.save => {
+                    println!("{}: saved {}", label, answer);
+                    expected.insert(*day, p.part, answer.to_string());
+                    passed += 1;
+                },
+                (Ok(answer), Some(want)) if answer.to_string() == want => {
+                    println!("{}: ok ({})", label, answer);
+                    passed += 1;
+                },
+                (Ok(answer), Some(want)) => {
+                    println!("{}: MISMATCH, expected {} but got {}", label, want, answer);
+                    failed += 1;
+                },
+                (Ok(answer), None) => {
+                    println!("{}: no expected answer stored ({})", label, answer);
+                    unchecked += 1;
+                },
+            }
+        }
+    }
 
-    println!("Day 7, Part 1 answer is {:?}", day7::part_one());
-    println!("Day 7, Part 2 answer is {:?}\n", day7::part_two());
+    if options.save {
+        if let Err(e) = expected.save(path) {
+            eprintln!("Couldn't save answers to {}: {}", path, e);
+            std::process::exit(2);
+        }
+        println!("\n{} answers saved to {}, {} failed.", passed, path, failed);
+    } else {
+        println!("\n{} passed, {} failed, {} unchecked.", passed, failed, unchecked);
+    }
+    if failed > 0 || unchecked > 0 {
+        std::process::exit(1);
+    }
+}
+
+// Audits the day 2 input against the chosen password policy rules and prints the report.
+fn audit(options: AuditOptions) {
+    let registry = RuleRegistry::default();
+    let rules: Vec<&dyn PolicyRule> = if options.rules.is_empty() {
+        registry.rules().collect()
+    } else {
+        options.rules.iter().map(|name| registry.get(name).unwrap_or_else(|| {
+            let names: Vec<&str> = registry.rules().map(|r| r.name()).collect();
+            eprintln!("Unknown rule '{}', the available rules are: {}", name, names.join(", "));
+            std::process::exit(2);
+        })).collect()
+    };
+
+    let input = match InputSource::from_arg(options.input.as_deref()).for_day(2).read() {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let audit = day2::audit(&input, &rules);
+    match options.format {
+        Format::Json => report::print_audit_json(&audit),
+        _ => report::print_audit_text(&audit),
+    }
+}
 
-    println!("Day 8, Part 1 answer is {:?}", day8::part_one());
-    println!("Day 8, Part 2 answer is {:?}\n", day8::part_two());
+// Draws the day 3 map with the chosen slope's path on it.
+fn trajectory(options: TrajectoryOptions) {
+    let map = InputSource::from_arg(options.input.as_deref()).for_day(3).read().and_then(|text| Day3.parse(&text));
+    match map {
+        Ok(map) => {
+            let (right, down) = options.slope;
+            print!("{}", day3::render_trajectory(&map, right, down, options.color));
+            println!("\nRight {}, down {}: {} trees hit.", right, down, day3::trees_on_slope(&map, right, down));
+        },
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        },
+    }
+}
+
+// Checks the day 4 passports against the schema and prints the errors of the invalid ones, or exports the valid ones.
+fn passports(options: PassportOptions) {
+    let schema = match options.schema.as_deref() {
+        Some(path) => Schema::load(path).unwrap_or_else(|e| {
+            eprintln!("Invalid schema {}: {}", path, e);
+            std::process::exit(2);
+        }),
+        None => day4::passport_schema(),
+    };
+    let reader = InputSource::from_arg(options.input.as_deref()).for_day(4).open().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+
+    // Passports are checked as they're read, so big batch files don't need to fit in memory.
+    let lenient = options.lenient;
+    let records = PassportReader::new(reader).map(|r| r.map(|mut record| {
+        if lenient {
+            record.normalize_height();
+        }
+        record
+    }));
+    if let Some(export) = options.export {
+        // Only valid passports are exported, anything unreadable is reported on stderr so the output stays clean. So are
+        // passports that pass a custom schema but can't be exported, e.g. because it makes a field export needs optional.
+        let valid = records.filter_map(|r| match r {
+            Ok(record) => match Passport::from_fields(&record.fields, &schema) {
+                Ok(passport) => Some((record, passport)),
+                Err(errors) => {
+                    if schema.validate(&record.fields) {
+                        let errors: Vec<String> = errors.iter().filter(|e| e.kind.is_failure()).map(|e| e.to_string()).collect();
+                        eprintln!("Passport {} (lines {}-{}) can't be exported: {}", record.index, record.first_line, record.last_line, errors.join(", "));
+                    }
+                    None
+                },
+            },
+            Err(ReadError::Unreadable { index, first_line, last_line, error }) => {
+                eprintln!("Passport {} (lines {}-{}) is unreadable: {}", index, first_line, last_line, error);
+                None
+            },
+            Err(ReadError::Io(e)) => {
+                eprintln!("Couldn't read the rest of the passports: {}", e);
+                None
+            },
+        });
+        match export {
+            Export::JsonLines => report::print_passports_jsonl(valid),
+            Export::Csv => report::print_passports_csv(valid),
+        }
+        return
+    }
+    let checked = records.map(|r| r.map(|record| {
+        let errors = record.check(&schema);
+        (record, errors)
+    }));
+    match options.format {
+        Format::Json => report::print_passports_json(checked),
+        _ => report::print_passports_text(checked),
+    }
+}
+
+// Returns the solutions for the selected days, exiting if there aren't any or if a single --input is given for several days.
+fn select<'a>(solutions: &'a [Box<dyn Runner>], days: &DaySelection, input: Option<&str>) -> Vec<&'a dyn Runner> {
+    let selected: Vec<&dyn Runner> = solutions.iter().filter(|s| days.contains(s.day())).map(|s| s.as_ref()).collect();
+    if selected.is_empty() {
+        eprintln!("None of the selected days have a solution yet, see `list` for the available days.");
+        std::process::exit(1);
+    }
+
+    // A single file or stdin only makes sense when running one day, a directory works for any amount.
+    if let InputSource::Single(_) = InputSource::from_arg(input) {
+        if selected.len() > 1 {
+            eprintln!("--input must be a directory when running more than one day.");
+            std::process::exit(2);
+        }
+    }
+    selected
+}
+
+// Reads each day's input and runs the given parts against it `repeat` times.
+fn run_days(selected: &[&dyn Runner], input: Option<&str>, parts: &[usize], repeat: usize) -> Vec<(usize, Vec<DayRun>)> {
+    let source = InputSource::from_arg(input);
+    selected.iter().map(|s| {
+        let source = source.for_day(s.day());
+        let day_runs = match source.read() {
+            Ok(text) => (0..repeat).map(|_| s.run(&text, parts)).collect(),
+            Err(e) => vec![DayRun { parse_time: Duration::default(), parts: Err(e) }],
+        };
+        (s.day(), day_runs)
+    }).collect()
+}
 
-    println!("Day 9, Part 1 answer is {:?}", day9::part_one());
-    println!("Day 9, Part 2 answer is {:?}\n", day9::part_two());
-    Ok(())
+// Keeps only the last run of each day.
+fn last_runs(runs: Vec<(usize, Vec<DayRun>)>) -> Vec<(usize, DayRun)> {
+    runs.into_iter().filter_map(|(day, mut r)| r.pop().map(|r| (day, r))).collect()
 }
@@ -0,0 +1,244 @@
+//! Lengths with units, like the heights on day 4's passports.
+//!
+//! A measurement is a number followed by a unit, e.g. 183cm or 5.5ft. Strict parsing only takes a whole number directly
+//! followed by the unit, lenient parsing also copes with the ways people tend to write them: "183 CM", "6 feet", "in 60",
+//! "72in.", decimals or feet and inches together like "5ft 11in" or 6'2".
+
+use std::fmt;
+
+/// How forgiving parsing should be.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    /// A whole number directly followed by a lower case unit symbol and nothing else.
+    Strict,
+    /// Also allows decimal numbers, surrounding whitespace, a space between the number and unit, the unit first, any case, unit names
+    /// (inches, centimetres, ...) and trailing text after the unit as long as it has no digits in it. A second
+    /// measurement in a smaller unit can follow the first, like 5ft 11in, and the two are added up.
+    Lenient,
+}
+
+/// The units a length can be in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unit {
+    Mm,
+    Cm,
+    M,
+    In,
+    Ft,
+}
+
+impl Unit {
+
+    /// The unit's symbol, which is how it's written in strict mode.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::Mm => "mm",
+            Unit::Cm => "cm",
+            Unit::M => "m",
+            Unit::In => "in",
+            Unit::Ft => "ft",
+        }
+    }
+
+    /// Looks up a unit by its symbol, or in lenient mode by any of its names in any case.
+    pub fn from_name(name: &str, mode: Mode) -> Option<Unit> {
+        let units = [Unit::Mm, Unit::Cm, Unit::M, Unit::In, Unit::Ft];
+        if let Some(unit) = units.iter().find(|u| u.symbol() == name) {
+            return Some(*unit)
+        }
+        if mode == Mode::Strict {
+            return None
+        }
+        match name.to_lowercase().as_str() {
+            "mm" | "millimetre" | "millimetres" | "millimeter" | "millimeters" => Some(Unit::Mm),
+            "cm" | "centimetre" | "centimetres" | "centimeter" | "centimeters" => Some(Unit::Cm),
+            "m" | "metre" | "metres" | "meter" | "meters" => Some(Unit::M),
+            "in" | "inch" | "inches" | "\"" => Some(Unit::In),
+            "ft" | "foot" | "feet" | "'" => Some(Unit::Ft),
+            _ => None,
+        }
+    }
+
+    // How many millimetres one of this unit is.
+    fn millimetres(&self) -> f64 {
+        match self {
+            Unit::Mm => 1.0,
+            Unit::Cm => 10.0,
+            Unit::M => 1000.0,
+            Unit::In => 25.4,
+            Unit::Ft => 304.8,
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+/// A length and the unit it's in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub value: f64,
+    pub unit: Unit,
+}
+
+/// Why a measurement couldn't be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeasureError {
+    Empty,
+    /// There's no number, or it isn't a valid one.
+    BadNumber(String),
+    /// There's no unit after the number.
+    MissingUnit,
+    UnknownUnit(String),
+    /// The unit is a known one, but not one of those the value can be in.
+    UnitNotAllowed(Unit),
+    /// Something that looks like another number follows the unit, but isn't a smaller unit that can be added on.
+    Trailing(String),
+}
+
+impl fmt::Display for MeasureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MeasureError::Empty => write!(f, "no measurement"),
+            MeasureError::BadNumber(n) => write!(f, "'{}' isn't a number", n),
+            MeasureError::MissingUnit => write!(f, "no unit"),
+            MeasureError::UnknownUnit(u) => write!(f, "unknown unit '{}'", u),
+            MeasureError::UnitNotAllowed(u) => write!(f, "'{}' isn't allowed here", u),
+            MeasureError::Trailing(t) => write!(f, "unexpected '{}' after the unit", t),
+        }
+    }
+}
+
+impl Measurement {
+
+    pub fn new(value: f64, unit: Unit) -> Measurement {
+        Measurement { value, unit }
+    }
+
+    /// Parses a measurement like 183cm, see Mode for what each mode accepts.
+    pub fn parse(s: &str, mode: Mode) -> Result<Measurement, MeasureError> {
+        let s = match mode {
+            Mode::Strict => s,
+            Mode::Lenient => s.trim(),
+        };
+        if s.is_empty() {
+            return Err(MeasureError::Empty)
+        }
+
+        // Split into the number and unit, in lenient mode the unit can come first.
+        let is_number = |c: char| c.is_ascii_digit() || c == '.';
+        let (number, unit) = if mode == Mode::Lenient && s.starts_with(char::is_alphabetic) {
+            let split = s.find(is_number).unwrap_or(s.len());
+            (&s[split..], &s[..split])
+        } else {
+            let split = s.find(|c: char| !is_number(c)).unwrap_or(s.len());
+            (&s[..split], &s[split..])
+        };
+        let (number, unit) = match mode {
+            Mode::Strict => (number, unit),
+            Mode::Lenient => (number.trim(), unit.trim()),
+        };
+
+        let bad_number = if number.is_empty() { s } else { number };
+        let value = parse_number(number, mode).ok_or_else(|| MeasureError::BadNumber(bad_number.to_string()))?;
+        if unit.is_empty() {
+            return Err(MeasureError::MissingUnit)
+        }
+        let (known, rest) = match mode {
+            Mode::Strict => (Unit::from_name(unit, mode), ""),
+            // Take the longest unit name at the start and look at what's left after it separately.
+            Mode::Lenient => unit.char_indices()
+                .map(|(i, c)| i + c.len_utf8())
+                .rev()
+                .find_map(|end| Unit::from_name(&unit[..end], mode).map(|u| (Some(u), &unit[end..])))
+                .unwrap_or((None, "")),
+        };
+        let unit = known.ok_or_else(|| MeasureError::UnknownUnit(unit.to_string()))?;
+        let measurement = Measurement { value, unit };
+
+        // Text without digits is dropped, so 183cmx is read as 183cm, anything with digits has to be a smaller unit to
+        // add on (the 11in of 5ft 11in), rather than being quietly lost.
+        if !rest.contains(|c: char| c.is_ascii_digit()) {
+            return Ok(measurement)
+        }
+        let trailing = || MeasureError::Trailing(rest.trim().to_string());
+        let smaller = Measurement::parse(rest, mode).map_err(|_| trailing())?;
+        if smaller.unit.millimetres() >= unit.millimetres() {
+            return Err(trailing())
+        }
+        Ok(Measurement { value: measurement.convert(smaller.unit).value + smaller.value, unit: smaller.unit })
+    }
+
+    /// The same length in another unit.
+    pub fn convert(&self, unit: Unit) -> Measurement {
+        Measurement { value: self.value * self.unit.millimetres() / unit.millimetres(), unit }
+    }
+}
+
+impl fmt::Display for Measurement {
+    /// Writes the measurement the way strict mode reads it, whole numbers without a decimal point.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.value, self.unit)
+    }
+}
+
+// Parses a plain number, digits with at most one '.' between them in lenient mode, only digits in strict mode. No signs,
+// exponents, inf or NaN.
+fn parse_number(s: &str, mode: Mode) -> Option<f64> {
+    let (whole, fraction) = match mode {
+        Mode::Strict => (s, "0"),
+        Mode::Lenient => s.split_once('.').unwrap_or((s, "0")),
+    };
+    let digits = |d: &str| !d.is_empty() && d.chars().all(|c| c.is_ascii_digit());
+    if digits(whole) && digits(fraction) {
+        s.parse().ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MeasureError, Measurement, Mode, Unit};
+
+    fn parse(s: &str, mode: Mode) -> Result<Measurement, MeasureError> {
+        Measurement::parse(s, mode)
+    }
+
+    #[test]
+    fn unit_first() {
+        assert_eq!(parse("cm150", Mode::Strict), Err(MeasureError::BadNumber(String::from("cm150"))));
+        assert_eq!(parse("cm150", Mode::Lenient), Ok(Measurement::new(150.0, Unit::Cm)));
+    }
+
+    #[test]
+    fn trailing_text() {
+        assert_eq!(parse("183cmx", Mode::Strict), Err(MeasureError::UnknownUnit(String::from("cmx"))));
+        assert_eq!(parse("183cmx", Mode::Lenient), Ok(Measurement::new(183.0, Unit::Cm)));
+        // A bare number after the unit could be cm or anything else, so it isn't guessed at.
+        assert_eq!(parse("1m80", Mode::Lenient), Err(MeasureError::Trailing(String::from("80"))));
+        assert_eq!(parse("1m80", Mode::Strict), Err(MeasureError::UnknownUnit(String::from("m80"))));
+    }
+
+    #[test]
+    fn smaller_units_add_on() {
+        // Converting feet to inches goes through millimetres, so it can be a hair off.
+        let inches = |s| parse(s, Mode::Lenient).map(|m| ((m.value * 1e6).round() / 1e6, m.unit));
+        assert_eq!(inches("5ft 11in"), Ok((71.0, Unit::In)));
+        assert_eq!(inches("6'2\""), Ok((74.0, Unit::In)));
+        assert_eq!(parse("5ft 11in", Mode::Strict), Err(MeasureError::UnknownUnit(String::from("ft 11in"))));
+        assert_eq!(parse("2in 5ft", Mode::Lenient), Err(MeasureError::Trailing(String::from("5ft"))));
+    }
+
+    #[test]
+    fn decimals_are_only_lenient() {
+        assert_eq!(parse("183.0cm", Mode::Strict), Err(MeasureError::BadNumber(String::from("183.0"))));
+        assert_eq!(parse("5.5ft", Mode::Strict), Err(MeasureError::BadNumber(String::from("5.5"))));
+        assert_eq!(parse("183.0cm", Mode::Lenient), Ok(Measurement::new(183.0, Unit::Cm)));
+        assert_eq!(parse("1.8m", Mode::Lenient), Ok(Measurement::new(1.8, Unit::M)));
+        assert_eq!(parse("1..8m", Mode::Lenient), Err(MeasureError::BadNumber(String::from("1..8"))));
+    }
+}
@@ -0,0 +1,497 @@
+//! Output for the runner: the answer/timing table, JSON and CSV answer records, and the benchmark report.
+
+use std::time::Duration;
+
+use advent_of_code::day2::{Audit, Observed};
+use advent_of_code::day4::{Passport, PassportRecord, ReadError};
+use advent_of_code::error::Error;
+use advent_of_code::schema::FieldError;
+use advent_of_code::solution::{Answer, DayRun};
+
+/// Whether a part produced an answer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    Ok,
+    NoAnswer,
+    Error,
+}
+
+impl Status {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::NoAnswer => "no-answer",
+            Status::Error => "error",
+        }
+    }
+}
+
+/// A single day/part result, flattened for the machine readable formats.
+#[derive(Debug)]
+pub struct Record<'a> {
+    pub day: usize,
+    pub part: usize,
+    pub answer: Option<&'a Answer>,
+    pub status: Status,
+    pub elapsed: Duration,
+    pub error: Option<String>,
+}
+
+/// Flattens day runs into one record per part. If a day's input couldn't be read or parsed every requested part is an error.
+pub fn records<'a>(runs: &'a [(usize, DayRun)], parts: &[usize]) -> Vec<Record<'a>> {
+    let mut records = Vec::new();
+    for (day, run) in runs {
+        match &run.parts {
+            Ok(part_runs) => {
+                for p in part_runs {
+                    let (answer, status, error) = match &p.answer {
+                        Ok(answer) => (Some(answer), Status::Ok, None),
+                        Err(Error::NoSolution) => (None, Status::NoAnswer, None),
+                        Err(e) => (None, Status::Error, Some(e.to_string())),
+                    };
+                    records.push(Record { day: *day, part: p.part, answer, status, elapsed: p.elapsed, error });
+                }
+            },
+            Err(e) => {
+                for part in parts {
+                    records.push(Record {
+                        day: *day,
+                        part: *part,
+                        answer: None,
+                        status: Status::Error,
+                        elapsed: Duration::default(),
+                        error: Some(e.to_string()),
+                    });
+                }
+            },
+        }
+    }
+    records
+}
+
+/// Prints the records as a JSON array of objects, one per line.
+pub fn print_json(records: &[Record]) {
+    println!("[");
+    for (i, r) in records.iter().enumerate() {
+        let answer = match r.answer {
+            Some(Answer::Number(n)) => n.to_string(),
+            _ => String::from("null"),
+        };
+        let error = r.error.as_deref().map_or(String::from("null"), json_string);
+        println!(
+            "  {{\"day\": {}, \"part\": {}, \"answer\": {}, \"status\": \"{}\", \"elapsed_us\": {:.1}, \"error\": {}}}{}",
+            r.day, r.part, answer, r.status.as_str(), micros(r.elapsed), error,
+            if i + 1 < records.len() { "," } else { "" }
+        );
+    }
+    println!("]");
+}
+
+/// Prints the records as CSV with a header line.
+pub fn print_csv(records: &[Record]) {
+    println!("day,part,answer,status,elapsed_us,error");
+    for r in records {
+        println!(
+            "{},{},{},{},{:.1},{}",
+            r.day, r.part,
+            r.answer.map_or(String::new(), |a| a.to_string()),
+            r.status.as_str(),
+            micros(r.elapsed),
+            csv_field(r.error.as_deref().unwrap_or(""))
+        );
+    }
+}
+
+/// Prints the answers and timings of each day as a table, with the total time at the bottom.
+/// Any errors are listed underneath the table.
+pub fn print_table(runs: &[(usize, DayRun)]) {
+    let mut rows = vec![header(&["Day", "Part 1", "Part 2", "Parse", "Part 1 time", "Part 2 time", "Total"])];
+    let mut errors = Vec::new();
+    let (mut total_parse, mut total_parts) = (Duration::default(), [Duration::default(); 2]);
+
+    for (day, run) in runs {
+        let mut answers = [String::from("-"), String::from("-")];
+        let mut times = [String::from("-"), String::from("-")];
+        let mut total = run.parse_time;
+
+        match &run.parts {
+            Ok(parts) => {
+                for p in parts {
+                    let i = p.part - 1;
+                    answers[i] = match &p.answer {
+                        Ok(answer) => answer.to_string(),
+                        Err(e) => {
+                            errors.push(format!("Day {} Part {}: {}", day, p.part, e));
+                            String::from("error")
+                        },
+                    };
+                    times[i] = format_duration(p.elapsed);
+                    total_parts[i] += p.elapsed;
+                    total += p.elapsed;
+                }
+            },
+            Err(e) => {
+                errors.push(format!("Day {}: {}", day, e));
+                answers = [String::from("error"), String::from("error")];
+            },
+        }
+        total_parse += run.parse_time;
+
+        let [a1, a2] = answers;
+        let [t1, t2] = times;
+        rows.push(vec![day.to_string(), a1, a2, format_duration(run.parse_time), t1, t2, format_duration(total)]);
+    }
+
+    rows.push(Vec::new());
+    rows.push(vec![
+        String::from("Total"), String::new(), String::new(),
+        format_duration(total_parse),
+        format_duration(total_parts[0]),
+        format_duration(total_parts[1]),
+        format_duration(total_parse + total_parts[0] + total_parts[1]),
+    ]);
+    print_rows(&rows);
+
+    if !errors.is_empty() {
+        println!();
+        for e in errors {
+            println!("{}", e);
+        }
+    }
+}
+
+/// Prints the min/median/max time of each step of each day, from several runs of it.
+pub fn print_bench(runs: &[(usize, Vec<DayRun>)]) {
+    let mut rows = vec![header(&["Day", "Step", "Runs", "Min", "Median", "Max"])];
+    let mut errors = Vec::new();
+
+    for (day, day_runs) in runs {
+        let mut parse = Vec::new();
+        let mut parts: [Vec<Duration>; 2] = [Vec::new(), Vec::new()];
+
+        for (n, run) in day_runs.iter().enumerate() {
+            // Every run hits the same errors, only report them once.
+            match &run.parts {
+                Ok(ps) => {
+                    parse.push(run.parse_time);
+                    for p in ps {
+                        match &p.answer {
+                            Err(e) if n == 0 => errors.push(format!("Day {} Part {}: {}", day, p.part, e)),
+                            _ => {},
+                        }
+                        parts[p.part - 1].push(p.elapsed);
+                    }
+                },
+                Err(e) if n == 0 => errors.push(format!("Day {}: {}", day, e)),
+                Err(_) => {},
+            }
+        }
+
+        let [part_one, part_two] = parts;
+        for (step, mut samples) in [("parse", parse), ("part 1", part_one), ("part 2", part_two)] {
+            if let Some((min, median, max)) = summarize(&mut samples) {
+                rows.push(vec![
+                    day.to_string(), step.to_string(), samples.len().to_string(),
+                    format_duration(min), format_duration(median), format_duration(max),
+                ]);
+            }
+        }
+    }
+    print_rows(&rows);
+
+    if !errors.is_empty() {
+        println!();
+        for e in errors {
+            println!("{}", e);
+        }
+    }
+}
+
+/// Prints each line of a password audit with what every rule found, then a summary of the failure reasons.
+pub fn print_audit_text(audit: &Audit) {
+    for line in &audit.lines {
+        println!("Line {}: {} ({})", line.line, if line.is_valid() { "valid" } else { "INVALID" }, line.text);
+        match &line.checks {
+            Ok(checks) => {
+                for (rule, check) in checks {
+                    let result = check.reason.as_deref().unwrap_or("ok");
+                    println!("    {}: {}, {}", rule, result, check.observed);
+                }
+            },
+            Err(e) => println!("    malformed: {}", e),
+        }
+    }
+
+    let valid = audit.valid_count();
+    println!();
+    println!("{} valid, {} invalid, {} lines checked.", valid, audit.lines.len() - valid, audit.lines.len());
+
+    let histogram = audit.failure_histogram();
+    if !histogram.is_empty() {
+        println!();
+        let mut rows = vec![header(&["Failure", "Lines"])];
+        rows.extend(histogram.iter().map(|(reason, count)| vec![reason.clone(), count.to_string()]));
+        print_rows(&rows);
+    }
+}
+
+/// Prints a password audit as a single JSON object, with a record per line and the failure reason counts.
+pub fn print_audit_json(audit: &Audit) {
+    println!("{{");
+    println!("  \"lines\": [");
+    for (i, line) in audit.lines.iter().enumerate() {
+        let (checks, error) = match &line.checks {
+            Ok(checks) => {
+                let checks: Vec<String> = checks.iter().map(|(rule, check)| {
+                    let observed = match &check.observed {
+                        Observed::Count(n) => format!("\"count\": {}", n),
+                        Observed::Positions(positions) => {
+                            let positions: Vec<String> = positions.iter().map(|(pos, c)| {
+                                let c = c.map_or(String::from("null"), |c| json_string(&c.to_string()));
+                                format!("{{\"position\": {}, \"char\": {}}}", pos, c)
+                            }).collect();
+                            format!("\"positions\": [{}]", positions.join(", "))
+                        },
+                    };
+                    format!(
+                        "{{\"rule\": {}, \"valid\": {}, {}, \"reason\": {}}}",
+                        json_string(rule), check.valid, observed,
+                        check.reason.as_deref().map_or(String::from("null"), json_string)
+                    )
+                }).collect();
+                (checks, String::from("null"))
+            },
+            Err(e) => (Vec::new(), json_string(&e.to_string())),
+        };
+        println!(
+            "    {{\"line\": {}, \"text\": {}, \"valid\": {}, \"checks\": [{}], \"error\": {}}}{}",
+            line.line, json_string(&line.text), line.is_valid(), checks.join(", "), error,
+            if i + 1 < audit.lines.len() { "," } else { "" }
+        );
+    }
+    println!("  ],");
+
+    let valid = audit.valid_count();
+    println!("  \"valid\": {},", valid);
+    println!("  \"invalid\": {},", audit.lines.len() - valid);
+    let failures: Vec<String> = audit.failure_histogram().iter().map(|(reason, count)| format!("{}: {}", json_string(reason), count)).collect();
+    println!("  \"failures\": {{{}}}", failures.join(", "));
+    println!("}}");
+}
+
+/// Prints the field errors of each invalid passport as they come in, then how many passports are valid and how often
+/// each kind of error came up. Passports that couldn't be read are listed along with the invalid ones, and valid
+/// passports with unknown fields are listed too, though they still count as valid.
+pub fn print_passports_text<I: Iterator<Item = Result<(PassportRecord, Vec<FieldError>), ReadError>>>(checked: I) {
+    let (mut valid, mut invalid, mut unreadable) = (0, 0, 0);
+    let mut kinds: Vec<(&str, usize)> = Vec::new();
+    let mut read_error = None;
+
+    for result in checked {
+        let (record, errors) = match result {
+            Ok(checked) => checked,
+            Err(ReadError::Unreadable { index, first_line, last_line, error }) => {
+                println!("Passport {} (lines {}-{}):", index, first_line, last_line);
+                println!("    unreadable: {}", error);
+                unreadable += 1;
+                continue;
+            },
+            Err(ReadError::Io(e)) => {
+                read_error = Some(e);
+                continue;
+            },
+        };
+        if errors.iter().any(|e| e.kind.is_failure()) {
+            invalid += 1;
+        } else {
+            valid += 1;
+        }
+        if errors.is_empty() {
+            continue;
+        }
+        println!("Passport {} (lines {}-{}):", record.index, record.first_line, record.last_line);
+        for e in errors {
+            println!("    {}", e);
+            match kinds.iter_mut().find(|(kind, _)| *kind == e.kind.as_str()) {
+                Some((_, count)) => *count += 1,
+                None => kinds.push((e.kind.as_str(), 1)),
+            }
+        }
+    }
+
+    if unreadable > 0 || !kinds.is_empty() {
+        println!();
+    }
+    if let Some(e) = read_error {
+        println!("Couldn't read the rest of the passports: {}", e);
+    }
+    println!("{} valid, {} invalid, {} unreadable, {} passports checked.", valid, invalid, unreadable, valid + invalid + unreadable);
+
+    if !kinds.is_empty() {
+        println!();
+        kinds.sort();
+        let mut rows = vec![header(&["Error", "Fields"])];
+        rows.extend(kinds.iter().map(|(kind, count)| vec![kind.to_string(), count.to_string()]));
+        print_rows(&rows);
+    }
+}
+
+/// Prints every passport's position, validity and field errors as a JSON object, writing each passport as it comes in.
+/// Passports that couldn't be read are listed in their place with why under "unreadable", which is null for the rest. An
+/// io error that stopped the reading is given under "error".
+pub fn print_passports_json<I: Iterator<Item = Result<(PassportRecord, Vec<FieldError>), ReadError>>>(checked: I) {
+    let (mut valid, mut invalid, mut unreadable) = (0, 0, 0);
+    let mut read_error = None;
+
+    println!("{{");
+    print!("  \"passports\": [");
+    for result in checked {
+        let (record, errors) = match result {
+            Ok(checked) => checked,
+            Err(ReadError::Unreadable { index, first_line, last_line, error }) => {
+                if valid + invalid + unreadable > 0 {
+                    print!(",");
+                }
+                unreadable += 1;
+                print!(
+                    "\n    {{\"index\": {}, \"first_line\": {}, \"last_line\": {}, \"valid\": false, \"unreadable\": {}, \"errors\": []}}",
+                    index, first_line, last_line, json_string(&error.to_string())
+                );
+                continue;
+            },
+            Err(ReadError::Io(e)) => {
+                read_error = Some(e);
+                continue;
+            },
+        };
+        if valid + invalid + unreadable > 0 {
+            print!(",");
+        }
+        let is_valid = !errors.iter().any(|e| e.kind.is_failure());
+        if is_valid {
+            valid += 1;
+        } else {
+            invalid += 1;
+        }
+        let errors: Vec<String> = errors.iter().map(|e| format!(
+            "{{\"field\": {}, \"kind\": \"{}\", \"value\": {}}}",
+            json_string(&e.field), e.kind.as_str(), e.value.as_deref().map_or(String::from("null"), json_string)
+        )).collect();
+        print!(
+            "\n    {{\"index\": {}, \"first_line\": {}, \"last_line\": {}, \"valid\": {}, \"unreadable\": null, \"errors\": [{}]}}",
+            record.index, record.first_line, record.last_line, is_valid, errors.join(", ")
+        );
+    }
+    println!("\n  ],");
+    println!("  \"valid\": {},", valid);
+    println!("  \"invalid\": {},", invalid);
+    println!("  \"unreadable\": {},", unreadable);
+    println!("  \"error\": {}", read_error.map_or(String::from("null"), |e: Error| json_string(&e.to_string())));
+    println!("}}");
+}
+
+/// Prints each passport as a JSON object on its own line, along with its position in the batch.
+pub fn print_passports_jsonl<I: Iterator<Item = (PassportRecord, Passport)>>(passports: I) {
+    for (record, p) in passports {
+        println!(
+            "{{\"index\": {}, \"birth_year\": {}, \"issue_year\": {}, \"expiration_year\": {}, \"height\": {}, \"height_unit\": \"{}\", \"hair_color\": \"{}\", \"eye_color\": \"{}\", \"passport_id\": {}, \"country_id\": {}}}",
+            record.index, p.birth_year, p.issue_year, p.expiration_year, p.height.value(), p.height.unit(), p.hair_color,
+            p.eye_color, json_string(&p.passport_id), p.country_id.as_deref().map_or(String::from("null"), json_string)
+        );
+    }
+}
+
+/// Prints the passports as CSV with a header line, along with their position in the batch.
+pub fn print_passports_csv<I: Iterator<Item = (PassportRecord, Passport)>>(passports: I) {
+    println!("index,birth_year,issue_year,expiration_year,height,height_unit,hair_color,eye_color,passport_id,country_id");
+    for (record, p) in passports {
+        println!(
+            "{},{},{},{},{},{},{},{},{},{}",
+            record.index, p.birth_year, p.issue_year, p.expiration_year, p.height.value(), p.height.unit(), p.hair_color,
+            p.eye_color, csv_field(&p.passport_id), csv_field(p.country_id.as_deref().unwrap_or(""))
+        );
+    }
+}
+
+/// Returns the (min, median, max) of the given durations, or None if there aren't any.
+pub fn summarize(samples: &mut [Duration]) -> Option<(Duration, Duration, Duration)> {
+    if samples.is_empty() {
+        return None
+    }
+    samples.sort();
+
+    // For an odd number of samples both of these are the middle one, otherwise they're the two either side of it.
+    let (low, high) = ((samples.len() - 1) / 2, samples.len() / 2);
+    let median = (samples[low] + samples[high]) / 2;
+    Some((samples[0], median, samples[samples.len() - 1]))
+}
+
+/// Formats a duration with a unit that suits its size, e.g. 12.3µs, 4.56ms or 1.234s.
+pub fn format_duration(d: Duration) -> String {
+    let micros = micros(d);
+    if micros < 1_000.0 {
+        format!("{:.1}µs", micros)
+    } else if micros < 1_000_000.0 {
+        format!("{:.2}ms", micros / 1_000.0)
+    } else {
+        format!("{:.3}s", micros / 1_000_000.0)
+    }
+}
+
+// Duration in fractional microseconds.
+fn micros(d: Duration) -> f64 {
+    d.as_secs_f64() * 1_000_000.0
+}
+
+// Quotes and escapes a string for JSON.
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// Quotes a CSV field if it contains a separator, quote or newline.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+// Turns column names into a header row.
+fn header(columns: &[&str]) -> Vec<String> {
+    columns.iter().map(|c| c.to_string()).collect()
+}
+
+// Prints rows as right aligned columns, the first row is treated as the header. Empty rows are drawn as a separator.
+fn print_rows(rows: &[Vec<String>]) {
+    let columns = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns).map(|i| {
+        rows.iter().filter_map(|r| r.get(i)).map(|c| c.chars().count()).max().unwrap_or(0)
+    }).collect();
+    let separator = widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<String>>().join("-+-");
+
+    for (i, row) in rows.iter().enumerate() {
+        if row.is_empty() {
+            println!("{}", separator);
+            continue;
+        }
+        let cells: Vec<String> = row.iter().zip(&widths).map(|(c, w)| format!("{:>w$}", c, w = w)).collect();
+        println!("{}", cells.join(" | "));
+        if i == 0 {
+            println!("{}", separator);
+        }
+    }
+}
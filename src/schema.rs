@@ -0,0 +1,510 @@
+//! Declarative rules for documents made of key:value fields, like day 4's passports.
+//!
+//! A schema is read from a simple line based format, one field per line:
+//!
+//!   # name  presence  rule
+//!   byr     required  int 1920..=2002
+//!   hgt     required  unit cm:150..=193 in:59..=76
+//!   hcl     required  pattern #[0-9a-f]{6}
+//!   ecl     required  enum amb blu brn gry grn hzl oth
+//!   cid     optional  any
+//!
+//! Presence is `required` or `optional`. The rules are:
+//!   any                     any value
+//!   int MIN..=MAX           a whole number within the range
+//!   unit UNIT:MIN..=MAX ... a whole number directly followed by one of the units, within that unit's range
+//!   pattern PATTERN         the whole value matches the pattern, see Pattern
+//!   enum VALUE ...          one of the listed values
+//!
+//! Blank lines and lines starting with # are ignored. A # anywhere else is part of the line, e.g. in a pattern.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use crate::error::Error;
+
+/// The fields a document can have and the rules their values have to follow.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schema {
+    pub fields: Vec<FieldSpec>,
+}
+
+/// A single field of a schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldSpec {
+    pub name: String,
+    pub required: bool,
+    pub rule: Rule,
+}
+
+/// What a field's value has to look like.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rule {
+    Any,
+    Int(RangeInclusive<i64>),
+    /// A number with a unit suffix, each unit with its own range.
+    Unit(Vec<(String, RangeInclusive<i64>)>),
+    Pattern(Pattern),
+    Enum(Vec<String>),
+}
+
+impl Schema {
+
+    /// Reads a schema from a file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Schema, Error> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .map_err(|e| Error::Io(io::Error::new(e.kind(), format!("couldn't read schema {}: {}", path.display(), e))))?;
+        Schema::parse(&contents)
+    }
+
+    /// Parses a schema, see the module docs for the format.
+    pub fn parse(contents: &str) -> Result<Schema, Error> {
+        let mut fields: Vec<FieldSpec> = Vec::new();
+        for (i, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let invalid = |part: &str, message: &str| Error::parse_at(i + 1, line, part, message);
+
+            let (name, presence, rule) = match words.as_slice() {
+                [name, presence, rule, ..] => (*name, *presence, *rule),
+                _ => return Err(invalid(line.trim(), "expected 'name required|optional rule'")),
+            };
+            let required = match presence {
+                "required" => true,
+                "optional" => false,
+                _ => return Err(invalid(presence, "expected required or optional")),
+            };
+            if fields.iter().any(|f| f.name == name) {
+                return Err(invalid(name, "field is already defined"));
+            }
+
+            let args = &words[3..];
+            let rule = match rule {
+                "any" => Rule::Any,
+                "int" => match args {
+                    [range] => Rule::Int(parse_range(range).ok_or_else(|| invalid(range, "expected a range like 1920..=2002"))?),
+                    _ => return Err(invalid(rule, "int takes a single range")),
+                },
+                "unit" => {
+                    if args.is_empty() {
+                        return Err(invalid(rule, "unit needs at least one UNIT:MIN..=MAX"));
+                    }
+                    let units = args.iter().map(|arg| {
+                        let (unit, range) = arg.split_once(':').ok_or_else(|| invalid(arg, "expected UNIT:MIN..=MAX"))?;
+                        let range = parse_range(range).ok_or_else(|| invalid(range, "expected a range like 150..=193"))?;
+                        Ok((unit.to_string(), range))
+                    }).collect::<Result<Vec<_>, Error>>()?;
+                    Rule::Unit(units)
+                },
+                "pattern" => match args {
+                    [pattern] => Rule::Pattern(Pattern::parse(pattern).map_err(|message| invalid(pattern, &message))?),
+                    _ => return Err(invalid(rule, "pattern takes a single pattern without spaces")),
+                },
+                "enum" => {
+                    if args.is_empty() {
+                        return Err(invalid(rule, "enum needs at least one value"));
+                    }
+                    Rule::Enum(args.iter().map(|a| a.to_string()).collect())
+                },
+                _ => return Err(invalid(rule, "expected any, int, unit, pattern or enum")),
+            };
+            fields.push(FieldSpec { name: name.to_string(), required, rule });
+        }
+        Ok(Schema { fields })
+    }
+
+    /// Looks up a field by name.
+    pub fn field(&self, name: &str) -> Option<&FieldSpec> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// Returns true if the document has every required field and every field the schema knows about follows its rule.
+    /// Fields the schema doesn't know about are ignored.
+    pub fn validate(&self, document: &HashMap<String, String>) -> bool {
+        self.check(document).iter().all(|e| !e.kind.is_failure())
+    }
+
+    /// Lists everything wrong with the document: missing required fields and fields breaking their rule, in the order
+    /// the schema lists them, followed by any fields the schema doesn't know about. Only the unknown fields are there
+    /// to be reported, they don't make the document invalid (see FieldErrorKind::is_failure).
+    pub fn check(&self, document: &HashMap<String, String>) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        for field in &self.fields {
+            match document.get(&field.name) {
+                Some(value) => {
+                    if let Err(kind) = field.rule.check(value) {
+                        errors.push(FieldError { field: field.name.clone(), value: Some(value.clone()), kind });
+                    }
+                },
+                None if field.required => errors.push(FieldError { field: field.name.clone(), value: None, kind: FieldErrorKind::Missing }),
+                None => {},
+            }
+        }
+
+        let mut unknown: Vec<(&String, &String)> = document.iter().filter(|(k, _)| self.field(k).is_none()).collect();
+        unknown.sort();
+        for (field, value) in unknown {
+            errors.push(FieldError { field: field.clone(), value: Some(value.clone()), kind: FieldErrorKind::Unknown });
+        }
+        errors
+    }
+
+    /// Returns true if the document has every required field, without checking their values.
+    pub fn has_required(&self, document: &HashMap<String, String>) -> bool {
+        self.fields.iter().filter(|f| f.required).all(|f| document.contains_key(&f.name))
+    }
+}
+
+impl Rule {
+
+    /// Checks the value follows the rule. Numbers outside of their range are OutOfRange, anything else wrong is BadFormat.
+    pub fn check(&self, value: &str) -> Result<(), FieldErrorKind> {
+        let in_range = |n: i64, range: &RangeInclusive<i64>| if range.contains(&n) { Ok(()) } else { Err(FieldErrorKind::OutOfRange) };
+        match self {
+            Rule::Any => Ok(()),
+            Rule::Int(range) => in_range(parse_int(value).ok_or(FieldErrorKind::BadFormat)?, range),
+            Rule::Unit(units) => {
+                let (n, range) = units.iter()
+                    .find_map(|(unit, range)| Some((value.strip_suffix(unit.as_str()).and_then(parse_int)?, range)))
+                    .ok_or(FieldErrorKind::BadFormat)?;
+                in_range(n, range)
+            },
+            Rule::Pattern(pattern) if pattern.matches(value) => Ok(()),
+            Rule::Enum(values) if values.iter().any(|v| v == value) => Ok(()),
+            Rule::Pattern(_) | Rule::Enum(_) => Err(FieldErrorKind::BadFormat),
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    /// Writes the rule the same way it's written in a schema file.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rule::Any => write!(f, "any"),
+            Rule::Int(range) => write!(f, "int {}..={}", range.start(), range.end()),
+            Rule::Unit(units) => {
+                write!(f, "unit")?;
+                for (unit, range) in units {
+                    write!(f, " {}:{}..={}", unit, range.start(), range.end())?;
+                }
+                Ok(())
+            },
+            Rule::Pattern(pattern) => write!(f, "pattern {}", pattern.as_str()),
+            Rule::Enum(values) => write!(f, "enum {}", values.join(" ")),
+        }
+    }
+}
+
+/// Something wrong with one field of a document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub field: String,
+    /// The field's value, None if it's missing.
+    pub value: Option<String>,
+    pub kind: FieldErrorKind,
+}
+
+/// The ways a field can be wrong.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldErrorKind {
+    /// A required field isn't there.
+    Missing,
+    /// The value is a number outside of the allowed range.
+    OutOfRange,
+    /// The value doesn't have the right form.
+    BadFormat,
+    /// The schema doesn't have the field. This is only a warning, the document can still be valid.
+    Unknown,
+}
+
+impl FieldErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FieldErrorKind::Missing => "missing",
+            FieldErrorKind::OutOfRange => "out-of-range",
+            FieldErrorKind::BadFormat => "bad-format",
+            FieldErrorKind::Unknown => "unknown",
+        }
+    }
+
+    /// Whether the error makes the document invalid, which is all of them apart from Unknown.
+    pub fn is_failure(&self) -> bool {
+        *self != FieldErrorKind::Unknown
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.kind, &self.value) {
+            (FieldErrorKind::Missing, _) | (_, None) => write!(f, "{}: missing", self.field),
+            (FieldErrorKind::Unknown, Some(value)) => write!(f, "{}: unknown field ('{}')", self.field, value),
+            (kind, Some(value)) => write!(f, "{}: {} ('{}')", self.field, kind.as_str().replace('-', " "), value),
+        }
+    }
+}
+
+// Parses a MIN..=MAX range.
+fn parse_range(s: &str) -> Option<RangeInclusive<i64>> {
+    let (min, max) = s.split_once("..=")?;
+    Some(parse_int(min)?..=parse_int(max)?)
+}
+
+// Parses a whole number made of only digits and an optional leading minus, unlike str::parse a '+' isn't allowed.
+fn parse_int(s: &str) -> Option<i64> {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None
+    }
+    s.parse().ok()
+}
+
+/// A tiny regex-like pattern which has to match a whole value.
+///
+/// Supported are literal characters, `.` for any character, classes like `[0-9a-f]` or `[^,]`, `\d` for a digit,
+/// `\` to escape any other character, and the repeats `?`, `*`, `+`, `{n}` and `{n,m}` after any of those.
+///
+/// Patterns come from schema files, so matching is kept from blowing up on ones like `.*.*.*.*x`: it takes at most
+/// around (pattern length) x (value length)² steps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    source: String,
+    pieces: Vec<Piece>,
+}
+
+// A single part of a pattern and how many times it can repeat.
+#[derive(Debug, Clone, PartialEq)]
+struct Piece {
+    atom: Atom,
+    min: usize,
+    max: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Atom {
+    Char(char),
+    Any,
+    /// Inclusive character ranges, and whether the class is negated.
+    Class(Vec<(char, char)>, bool),
+}
+
+impl Atom {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Atom::Char(expected) => c == *expected,
+            Atom::Any => true,
+            Atom::Class(ranges, negated) => ranges.iter().any(|(low, high)| (*low..=*high).contains(&c)) != *negated,
+        }
+    }
+}
+
+impl Pattern {
+
+    /// Parses a pattern, returning a message saying what's wrong with it if it isn't valid.
+    pub fn parse(source: &str) -> Result<Pattern, String> {
+        let mut pieces = Vec::new();
+        let mut chars = source.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            let atom = match c {
+                '.' => Atom::Any,
+                '\\' => match chars.next() {
+                    Some('d') => Atom::Class(vec![('0', '9')], false),
+                    Some(c) => Atom::Char(c),
+                    None => return Err(String::from("pattern ends with a lone \\")),
+                },
+                '[' => {
+                    let negated = chars.next_if_eq(&'^').is_some();
+                    let mut ranges = Vec::new();
+                    loop {
+                        let low = match chars.next() {
+                            Some(']') if !ranges.is_empty() => break,
+                            Some('\\') => chars.next().ok_or("pattern ends inside a [class]")?,
+                            Some(c) => c,
+                            None => return Err(String::from("unclosed [class]")),
+                        };
+                        let high = if chars.peek() == Some(&'-') {
+                            chars.next();
+                            match chars.next() {
+                                // A trailing - is just a -.
+                                Some(']') => {
+                                    ranges.push((low, low));
+                                    ranges.push(('-', '-'));
+                                    break
+                                },
+                                Some(high) => high,
+                                None => return Err(String::from("unclosed [class]")),
+                            }
+                        } else {
+                            low
+                        };
+                        if high < low {
+                            return Err(format!("backwards range {}-{} in [class]", low, high))
+                        }
+                        ranges.push((low, high));
+                    }
+                    Atom::Class(ranges, negated)
+                },
+                '?' | '*' | '+' | '{' => return Err(format!("'{}' doesn't follow anything to repeat", c)),
+                c => Atom::Char(c),
+            };
+
+            let (min, max) = match chars.peek() {
+                Some('?') => (0, Some(1)),
+                Some('*') => (0, None),
+                Some('+') => (1, None),
+                Some('{') => {
+                    chars.next();
+                    let mut counts = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => counts.push(c),
+                            None => return Err(String::from("unclosed {repeat}")),
+                        }
+                    }
+                    let invalid = || format!("invalid repeat {{{}}}", counts);
+                    let (min, max) = match counts.split_once(',') {
+                        Some((min, max)) => (min.parse().map_err(|_| invalid())?, Some(max.parse().map_err(|_| invalid())?)),
+                        None => {
+                            let n = counts.parse().map_err(|_| invalid())?;
+                            (n, Some(n))
+                        },
+                    };
+                    if max.is_some_and(|max| max < min) {
+                        return Err(invalid())
+                    }
+                    pieces.push(Piece { atom, min, max });
+                    continue;
+                },
+                _ => (1, Some(1)),
+            };
+            if (min, max) != (1, Some(1)) {
+                chars.next();
+            }
+            pieces.push(Piece { atom, min, max });
+        }
+        Ok(Pattern { source: source.to_string(), pieces })
+    }
+
+    /// Returns true if the whole of the value matches the pattern.
+    pub fn matches(&self, value: &str) -> bool {
+        let chars: Vec<char> = value.chars().collect();
+        let mut failed = vec![false; (self.pieces.len() + 1) * (chars.len() + 1)];
+        matches_from(&self.pieces, &chars, 0, 0, &mut failed)
+    }
+
+    /// The pattern as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+// Matches pieces[piece..] against the whole of chars[position..], trying the longest repeat of each piece first and
+// backing off from there. Whether the rest matches only depends on where it starts, so every (piece, position) that
+// didn't match is marked in `failed` and never tried again, which is what stops the backtracking going exponential.
+fn matches_from(pieces: &[Piece], chars: &[char], piece: usize, position: usize, failed: &mut [bool]) -> bool {
+    let current = match pieces.get(piece) {
+        Some(p) => p,
+        None => return position == chars.len(),
+    };
+    let seen = piece * (chars.len() + 1) + position;
+    if failed[seen] {
+        return false
+    }
+    let rest = &chars[position..];
+    let max = current.max.unwrap_or(rest.len()).min(rest.len());
+    let available = rest[..max].iter().take_while(|c| current.atom.matches(**c)).count();
+    let matched = (current.min..=available).rev().any(|n| matches_from(pieces, chars, piece + 1, position + n, failed));
+    if !matched {
+        failed[seen] = true;
+    }
+    matched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pattern;
+
+    fn matches(pattern: &str, value: &str) -> bool {
+        Pattern::parse(pattern).unwrap().matches(value)
+    }
+
+    #[test]
+    fn classes() {
+        assert!(matches("[0-9a-f]", "c"));
+        assert!(matches("[0-9a-f]", "7"));
+        assert!(!matches("[0-9a-f]", "g"));
+        assert!(matches("[abc]+", "cab"));
+        assert!(matches("\\d\\d", "42"));
+        assert!(!matches("\\d", "x"));
+    }
+
+    #[test]
+    fn negated_classes() {
+        assert!(matches("[^,]+", "a b"));
+        assert!(!matches("[^,]+", "a,b"));
+        assert!(!matches("[^0-9]", "5"));
+    }
+
+    #[test]
+    fn trailing_dash_is_literal() {
+        assert!(matches("[a-]", "-"));
+        assert!(matches("[a-]", "a"));
+        assert!(!matches("[a-]", "b"));
+        assert!(matches("[0-9-]+", "12-34"));
+    }
+
+    #[test]
+    fn counted_repeats() {
+        assert!(matches("#[0-9a-f]{6}", "#123abc"));
+        assert!(!matches("#[0-9a-f]{6}", "#123ab"));
+        assert!(!matches("#[0-9a-f]{6}", "#123abcd"));
+        assert!(matches("a{2,3}", "aa"));
+        assert!(matches("a{2,3}", "aaa"));
+        assert!(!matches("a{2,3}", "a"));
+        assert!(!matches("a{2,3}", "aaaa"));
+        assert!(matches("x{0}y", "y"));
+    }
+
+    #[test]
+    fn escapes() {
+        assert!(matches("a\\.b", "a.b"));
+        assert!(!matches("a\\.b", "axb"));
+        assert!(matches("\\[\\]", "[]"));
+        assert!(matches("1\\+1", "1+1"));
+        assert!(matches("[\\]]", "]"));
+    }
+
+    #[test]
+    fn whole_value_is_matched() {
+        assert!(matches("[0-9]{9}", "000000001"));
+        assert!(!matches("[0-9]{9}", "0123456789"));
+        assert!(!matches("abc", "xabc"));
+        assert!(!matches("abc", "abcx"));
+        assert!(matches("a.*z", "abcz"));
+        assert!(!matches("a.*z", "abczq"));
+        assert!(matches("", ""));
+        assert!(!matches("", "a"));
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        for pattern in ["*a", "a\\", "[abc", "[z-a]", "a{3,1}", "a{x}", "a{2"] {
+            assert!(Pattern::parse(pattern).is_err(), "{} should be invalid", pattern);
+        }
+    }
+
+    #[test]
+    fn backtracking_is_bounded() {
+        let value = "a".repeat(500);
+        assert!(!matches(".*.*.*.*.*.*.*.*x", &value));
+        assert!(matches(".*.*.*.*.*.*.*.*a", &value));
+    }
+}
@@ -0,0 +1,113 @@
+//! Shared interface for every day's solution, and the registry of all solved days.
+//!
+//! Adding a new day is a matter of implementing Solution for it and adding it to `registry()`.
+//! Each day parses its input once into its own type, which both parts then share.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::error::Error;
+use crate::{day1, day2, day3, day4, day5, day6, day7, day8, day9};
+
+/// The answer to a single part of a day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i64),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Answer {
+        Answer::Number(n as i64)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(n: isize) -> Answer {
+        Answer::Number(n as i64)
+    }
+}
+
+/// A solution to both parts of a single day.
+pub trait Solution {
+    /// The parsed form of the puzzle input, built once and shared by both parts.
+    type Parsed;
+
+    /// The day of the advent calendar this solves.
+    fn day(&self) -> usize;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Error>;
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<Answer, Error>;
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<Answer, Error>;
+}
+
+/// The outcome of running a day: how long parsing took, and the answer and time taken for each part that was run.
+/// `parts` is an error if the input couldn't be parsed.
+#[derive(Debug)]
+pub struct DayRun {
+    pub parse_time: Duration,
+    pub parts: Result<Vec<PartRun>, Error>,
+}
+
+#[derive(Debug)]
+pub struct PartRun {
+    pub part: usize,
+    pub answer: Result<Answer, Error>,
+    pub elapsed: Duration,
+}
+
+/// Object safe wrapper around Solution so days with different parsed types can share the registry.
+pub trait Runner {
+    fn day(&self) -> usize;
+
+    /// Parses the input once and runs the given parts (1 and/or 2) against it.
+    fn run(&self, input: &str, parts: &[usize]) -> DayRun;
+}
+
+impl<S: Solution> Runner for S {
+    fn day(&self) -> usize {
+        Solution::day(self)
+    }
+
+    fn run(&self, input: &str, parts: &[usize]) -> DayRun {
+        let start = Instant::now();
+        let parsed = self.parse(input);
+        let parse_time = start.elapsed();
+
+        let parts = parsed.map(|parsed| {
+            parts.iter().map(|&part| {
+                let start = Instant::now();
+                let answer = match part {
+                    1 => self.part_one(&parsed),
+                    _ => self.part_two(&parsed),
+                };
+                PartRun { part, answer, elapsed: start.elapsed() }
+            }).collect()
+        });
+        DayRun { parse_time, parts }
+    }
+}
+
+/// Returns every solved day, in order.
+pub fn registry() -> Vec<Box<dyn Runner>> {
+    vec![
+        Box::new(day1::Day1),
+        Box::new(day2::Day2),
+        Box::new(day3::Day3),
+        Box::new(day4::Day4),
+        Box::new(day5::Day5),
+        Box::new(day6::Day6),
+        Box::new(day7::Day7),
+        Box::new(day8::Day8),
+        Box::new(day9::Day9),
+    ]
+}